@@ -0,0 +1,390 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Credential providers consulted by the provider clients before every request.
+//!
+//! A [`CredentialProvider`] supplies the API key of a client, so keys can be rotated or
+//! fetched from a secrets manager without rebuilding the client. When an API rejects a
+//! credential with `401 Unauthorized`, [`send_authenticated`] invalidates it and retries the
+//! request once with a fresh one.
+
+use std::{
+    fmt,
+    future::{Future, ready},
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant, SystemTime},
+};
+
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::{BoxFuture, Error, Result};
+
+/// A source of the credential used to authenticate requests.
+///
+/// A blanket implementation is provided for `Arc<T>`, so a provider can be shared by
+/// several clients.
+pub trait CredentialProvider: Send + Sync + fmt::Debug {
+    /// Returns the credential to authenticate the next request with.
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>>;
+
+    /// Discards any cached credential, so the next call to
+    /// [`CredentialProvider::credential`] fetches a fresh one.
+    ///
+    /// Called after the API rejects a credential.
+    fn invalidate(&self) {}
+}
+
+impl<T> CredentialProvider for Arc<T>
+where
+    T: CredentialProvider + ?Sized,
+{
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>> {
+        (**self).credential()
+    }
+
+    fn invalidate(&self) {
+        (**self).invalidate();
+    }
+}
+
+/// A credential that never changes.
+#[derive(Debug, Clone)]
+pub struct StaticCredential(SecretString);
+
+impl StaticCredential {
+    /// Creates a provider always returning `credential`.
+    #[must_use]
+    pub fn new(credential: SecretString) -> Self {
+        Self(credential)
+    }
+}
+
+impl CredentialProvider for StaticCredential {
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>> {
+        Box::pin(ready(Ok(self.0.clone())))
+    }
+}
+
+/// A credential read from an environment variable before every request.
+#[derive(Debug, Clone)]
+pub struct EnvCredential {
+    var: String,
+}
+
+impl EnvCredential {
+    /// Creates a provider reading the environment variable `var`.
+    #[must_use]
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl CredentialProvider for EnvCredential {
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>> {
+        let credential = std::env::var(&self.var)
+            .map(SecretString::from)
+            .map_err(|_| {
+                Error::CredentialError(format!("Environment variable {} is not set", self.var))
+            });
+
+        Box::pin(ready(credential))
+    }
+}
+
+/// A credential read from a file, and read again whenever the file is modified.
+///
+/// Leading and trailing whitespace, such as a final newline, is ignored.
+#[derive(Debug)]
+pub struct FileCredential {
+    path: PathBuf,
+    cache: Mutex<Option<(SystemTime, SecretString)>>,
+}
+
+impl FileCredential {
+    /// Creates a provider reading the file at `path`.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    fn read(&self) -> Result<SecretString> {
+        let modified = std::fs::metadata(&self.path)?.modified()?;
+
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((cached_modified, credential)) = &*cache
+            && *cached_modified == modified
+        {
+            return Ok(credential.clone());
+        }
+
+        let credential = SecretString::from(std::fs::read_to_string(&self.path)?.trim());
+        *cache = Some((modified, credential.clone()));
+
+        Ok(credential)
+    }
+}
+
+impl CredentialProvider for FileCredential {
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>> {
+        Box::pin(ready(self.read()))
+    }
+
+    fn invalidate(&self) {
+        *self.cache.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+type Callback = dyn Fn() -> BoxFuture<'static, Result<SecretString>> + Send + Sync;
+
+/// A credential fetched by an async callback, such as a call to a secrets manager.
+///
+/// The fetched credential is cached until it is invalidated or, if set, its time to live
+/// elapses.
+pub struct CallbackCredential {
+    callback: Arc<Callback>,
+    ttl: Option<Duration>,
+    cache: Mutex<Option<(Instant, SecretString)>>,
+}
+
+impl CallbackCredential {
+    /// Creates a provider fetching its credential with `callback`.
+    pub fn new<F, Fut>(callback: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<SecretString>> + Send + 'static,
+    {
+        Self {
+            callback: Arc::new(move || Box::pin(callback())),
+            ttl: None,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Fetches the credential again once `ttl` has elapsed since it was last fetched.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn cached(&self) -> Option<SecretString> {
+        let cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        let (fetched_at, credential) = cache.as_ref()?;
+
+        match self.ttl {
+            Some(ttl) if fetched_at.elapsed() >= ttl => None,
+            _ => Some(credential.clone()),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackCredential")
+            .field("ttl", &self.ttl)
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CredentialProvider for CallbackCredential {
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>> {
+        Box::pin(async move {
+            if let Some(credential) = self.cached() {
+                return Ok(credential);
+            }
+
+            let credential = (self.callback)().await?;
+            *self.cache.lock().unwrap_or_else(PoisonError::into_inner) =
+                Some((Instant::now(), credential.clone()));
+
+            Ok(credential)
+        })
+    }
+
+    fn invalidate(&self) {
+        *self.cache.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+/// Sends the request built by `build` with a credential from `credentials`.
+///
+/// If the API responds with `401 Unauthorized`, the credential is invalidated and, if the
+/// provider then returns a different credential, the request is sent once more with it.
+///
+/// # Errors
+///
+/// Returns an error if no credential can be obtained or the HTTP request fails.
+pub async fn send_authenticated<F>(
+    credentials: &dyn CredentialProvider,
+    build: F,
+) -> Result<reqwest::Response>
+where
+    F: Fn(&SecretString) -> reqwest::RequestBuilder,
+{
+    let credential = credentials.credential().await?;
+    let response = build(&credential).send().await?;
+
+    if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(response);
+    }
+
+    credentials.invalidate();
+    let refreshed = credentials.credential().await?;
+    if refreshed.expose_secret() == credential.expose_secret() {
+        return Ok(response);
+    }
+
+    Ok(build(&refreshed).send().await?)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::bearer_token};
+
+    #[tokio::test]
+    async fn test_file_credential_rereads_modified_file() {
+        let path = std::env::temp_dir().join(format!(
+            "latchlm-credential-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::write(&path, "first-key\n").unwrap();
+
+        let provider = FileCredential::new(&path);
+        assert_eq!(
+            provider.credential().await.unwrap().expose_secret(),
+            "first-key"
+        );
+
+        std::fs::write(&path, "second-key\n").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(
+            provider.credential().await.unwrap().expose_secret(),
+            "second-key"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        provider.invalidate();
+        assert!(matches!(
+            provider.credential().await,
+            Err(Error::IoError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_callback_credential_caches_until_invalidated() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = CallbackCredential::new({
+            let calls = Arc::clone(&calls);
+            move || {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(SecretString::from(format!("key-{call}"))) }
+            }
+        });
+
+        assert_eq!(
+            provider.credential().await.unwrap().expose_secret(),
+            "key-0"
+        );
+        assert_eq!(
+            provider.credential().await.unwrap().expose_secret(),
+            "key-0"
+        );
+
+        provider.invalidate();
+        assert_eq!(
+            provider.credential().await.unwrap().expose_secret(),
+            "key-1"
+        );
+
+        let provider = provider.with_ttl(Duration::ZERO);
+        assert_eq!(
+            provider.credential().await.unwrap().expose_secret(),
+            "key-2"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_env_credential_missing_variable() {
+        let provider = EnvCredential::new("LATCHLM_TEST_UNSET_VARIABLE");
+
+        assert!(matches!(
+            provider.credential().await,
+            Err(Error::CredentialError(_))
+        ));
+    }
+
+    fn rotating_credentials() -> CallbackCredential {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        CallbackCredential::new(move || {
+            let key = match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => "stale-key",
+                _ => "fresh-key",
+            };
+            async move { Ok(SecretString::from(key)) }
+        })
+    }
+
+    fn get(url: String) -> impl Fn(&SecretString) -> reqwest::RequestBuilder {
+        let client = reqwest::Client::new();
+        move |key| client.get(&url).bearer_auth(key.expose_secret())
+    }
+
+    #[tokio::test]
+    async fn test_send_authenticated_retries_with_refreshed_credential() {
+        let mock_server = MockServer::start().await;
+
+        let _stale_guard = Mock::given(bearer_token("stale-key"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount_as_scoped(&mock_server)
+            .await;
+
+        let _fresh_guard = Mock::given(bearer_token("fresh-key"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount_as_scoped(&mock_server)
+            .await;
+
+        let provider = rotating_credentials();
+        let build = get(mock_server.uri());
+
+        // The refreshed credential is cached for the following requests
+        for _ in 0..2 {
+            let response = send_authenticated(&provider, &build).await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_authenticated_returns_rejection_of_unchanged_credential() {
+        let mock_server = MockServer::start().await;
+
+        let _mock_guard = Mock::given(bearer_token("static-key"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount_as_scoped(&mock_server)
+            .await;
+
+        let provider = StaticCredential::new(SecretString::from("static-key"));
+        let response = send_authenticated(&provider, get(mock_server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+}
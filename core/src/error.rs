@@ -21,8 +21,29 @@ pub enum Error {
     #[error("Invalid model name: {0}")]
     InvalidModelError(String),
 
+    #[error("Invalid URL: {0}")]
+    InvalidUrlError(String),
+
     #[error("Provider settings error: {provider} : {error}")]
     ProviderError { provider: String, error: String },
+
+    #[error("Credential error: {0}")]
+    CredentialError(String),
+
+    #[error("Content blocked: {0}")]
+    ContentBlockedError(String),
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Stream error: {0}")]
+    StreamError(String),
+
+    #[error("The receiving end of the channel was closed")]
+    ChannelClosedError,
+
+    #[error("Budget exceeded: spent ${spent:.4} of ${budget:.4}")]
+    BudgetExceededError { spent: f64, budget: f64 },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
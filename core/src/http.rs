@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! HTTP response helpers shared by the provider clients.
+
+use std::future::ready;
+
+use eventsource_stream::Eventsource;
+use futures::{StreamExt, stream::BoxStream};
+use serde::de::DeserializeOwned;
+
+use crate::{Error, Result};
+
+/// Returns the response if its status is successful, or the API error it carries.
+///
+/// # Errors
+///
+/// Returns [`Error::ApiError`] with the status and body of an unsuccessful response, or
+/// [`Error::RequestError`] if the body cannot be read.
+pub async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status = response.status().as_u16();
+    let message = response.text().await?;
+
+    #[cfg(feature = "tracing")]
+    tracing::error!("API error: {}", &message);
+
+    Err(Error::ApiError { status, message })
+}
+
+/// Parses the JSON body of a response, or returns the API error it carries.
+///
+/// # Errors
+///
+/// Returns the errors of [`error_for_status`], or [`Error::ParseError`] if the body is not
+/// valid JSON for `T`.
+pub async fn parse_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let bytes = error_for_status(response).await?.bytes().await?;
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!("Received bytes: {:?}", bytes);
+
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Parses the server-sent events of a streaming response as JSON, or returns the API error
+/// it carries.
+///
+/// Errors reading the event stream are reported as [`Error::ProviderError`] for `provider`.
+///
+/// # Errors
+///
+/// Returns the errors of [`error_for_status`].
+pub async fn parse_event_stream<T: DeserializeOwned + 'static>(
+    response: reqwest::Response,
+    provider: &'static str,
+) -> Result<BoxStream<'static, Result<T>>> {
+    parse_events(response, provider, None).await
+}
+
+/// Parses the server-sent events of a streaming response as JSON like [`parse_event_stream`],
+/// ending the stream at the first event whose data is exactly `sentinel`, such as `[DONE]`.
+///
+/// # Errors
+///
+/// Returns the errors of [`error_for_status`].
+pub async fn parse_event_stream_until<T: DeserializeOwned + 'static>(
+    response: reqwest::Response,
+    provider: &'static str,
+    sentinel: &'static str,
+) -> Result<BoxStream<'static, Result<T>>> {
+    parse_events(response, provider, Some(sentinel)).await
+}
+
+async fn parse_events<T: DeserializeOwned + 'static>(
+    response: reqwest::Response,
+    provider: &'static str,
+    sentinel: Option<&'static str>,
+) -> Result<BoxStream<'static, Result<T>>> {
+    let response = error_for_status(response).await?;
+
+    let stream = response
+        .bytes_stream()
+        .eventsource()
+        .take_while(move |event| {
+            let is_sentinel = matches!(
+                (event, sentinel),
+                (Ok(event), Some(sentinel)) if event.data == sentinel
+            );
+            ready(!is_sentinel)
+        })
+        .map(move |event| {
+            let event = match event {
+                Ok(event) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("Received event: {:?}", event);
+
+                    event
+                }
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!("Error receiving event: {}", err);
+
+                    return Err(Error::ProviderError {
+                        provider: provider.to_owned(),
+                        error: err.to_string(),
+                    });
+                }
+            };
+
+            Ok(serde_json::from_str(&event.data)?)
+        });
+
+    Ok(Box::pin(stream))
+}
@@ -9,12 +9,39 @@
 //! This crate provides the foundation for the LatchLM ecosystem by defining
 //! the core abstractions used across all provider implementations.
 
+pub mod credentials;
+pub use credentials::*;
+
 pub mod error;
 pub use error::*;
 
+pub mod http;
+pub use http::*;
+
+pub mod pricing;
+pub use pricing::*;
+
+pub mod spend;
+pub use spend::*;
+
+pub mod stream;
+pub use stream::*;
+
+pub mod url;
+pub use url::*;
+
 use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, future::Future, pin::Pin, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    future::Future,
+    iter::Sum,
+    ops::{Add, AddAssign},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
 
 /// A `Future` type used by the `AiProvider` trait.
 ///
@@ -52,8 +79,8 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 ///
 ///     fn model_id(&self) -> ModelId {
 ///         match self {
-///             MyModel::Variant1 => ModelId { id: "mymodel-variant-1".into(), name: "My Model Variant 1".into()},
-///             MyModel::Variant2 => ModelId { id: "mymodel-variant-2".into(), name: "My Model Variant 2".into()},
+///             MyModel::Variant1 => ModelId { id: "mymodel-variant-1".into(), name: "My Model Variant 1".into(), ..Default::default() },
+///             MyModel::Variant2 => ModelId { id: "mymodel-variant-2".into(), name: "My Model Variant 2".into(), ..Default::default() },
 ///         }
 ///     }
 /// }
@@ -61,6 +88,11 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 pub trait AiModel: AsRef<str> + Send + Sync + 'static {
     fn as_any(&self) -> &dyn std::any::Any;
     fn model_id(&self) -> ModelId<'_>;
+
+    /// Returns what the model supports, as far as it is known.
+    fn capabilities(&self) -> ModelCapabilities<'_> {
+        self.model_id().capabilities
+    }
 }
 
 impl dyn AiModel {
@@ -71,12 +103,73 @@ impl dyn AiModel {
 }
 
 /// A unique identifier for an LLM model.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default)]
 pub struct ModelId<'a> {
     /// The technical identifier used in API requests
     pub id: Cow<'a, str>,
     /// A human-readable name
     pub name: Cow<'a, str>,
+    /// What the model supports, as far as it is known
+    #[serde(default)]
+    pub capabilities: ModelCapabilities<'a>,
+    /// A note explaining why the model is deprecated and what to use instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Cow<'a, str>>,
+}
+
+/// The capabilities of an LLM model.
+///
+/// Every field is optional: `None` or an empty list means the capability is unknown,
+/// not that it is unsupported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default)]
+pub struct ModelCapabilities<'a> {
+    /// The maximum number of input tokens
+    pub context_window: Option<u64>,
+    /// The maximum number of output tokens
+    pub max_output_tokens: Option<u64>,
+    /// The modalities accepted as input
+    #[serde(default)]
+    pub input_modalities: Cow<'a, [Modality]>,
+    /// The modalities the model can generate
+    #[serde(default)]
+    pub output_modalities: Cow<'a, [Modality]>,
+    /// Whether the model can call tools
+    pub tool_calling: Option<bool>,
+    /// Whether the model can constrain its output to a JSON schema
+    pub structured_output: Option<bool>,
+    /// Whether the model reasons before answering
+    pub reasoning: Option<bool>,
+    /// Whether responses can be streamed
+    pub streaming: Option<bool>,
+}
+
+impl ModelCapabilities<'_> {
+    /// Creates capabilities where nothing is known.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            context_window: None,
+            max_output_tokens: None,
+            input_modalities: Cow::Borrowed(&[]),
+            output_modalities: Cow::Borrowed(&[]),
+            tool_calling: None,
+            structured_output: None,
+            reasoning: None,
+            streaming: None,
+        }
+    }
+
+    /// Returns `true` if the model is known to accept `modality` as input.
+    #[must_use]
+    pub fn accepts(&self, modality: Modality) -> bool {
+        self.input_modalities.contains(&modality)
+    }
+
+    /// Returns `true` if the model is known to generate `modality`.
+    #[must_use]
+    pub fn generates(&self, modality: Modality) -> bool {
+        self.output_modalities.contains(&modality)
+    }
 }
 
 impl std::fmt::Display for ModelId<'_> {
@@ -86,30 +179,242 @@ impl std::fmt::Display for ModelId<'_> {
 }
 
 /// A request for an LLM.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct AiRequest {
     /// The input text to be processed by the model
     pub text: String,
+    /// Reasoning settings for models that support extended thinking
+    pub reasoning: Option<ReasoningOptions>,
+    /// The number of candidate responses to generate, for providers that support it
+    pub candidate_count: Option<u32>,
+}
+
+/// How much effort a reasoning model should spend thinking before answering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Minimal,
+    Low,
+    Medium,
+    High,
+}
+
+/// Reasoning settings for a request.
+///
+/// Providers map these settings to their own parameters and ignore the ones
+/// they do not support.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ReasoningOptions {
+    /// The reasoning effort requested from the model
+    pub effort: Option<ReasoningEffort>,
+    /// The maximum number of tokens the model may spend reasoning
+    pub budget_tokens: Option<u64>,
+    /// Whether the model's reasoning, or a summary of it, should be returned
+    pub include_reasoning: bool,
 }
 
 /// Response from an LLM API provider.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// When several candidates are generated, the top-level fields describe the
+/// first candidate and every candidate is available in [`AiResponse::candidates`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct AiResponse {
-    /// The text response
+    /// The text response of the first candidate
     pub text: String,
+    /// The model's reasoning, or a summary of it, when returned by the provider
+    pub reasoning: Option<String>,
     /// Token usage data
     pub token_usage: TokenUsage,
+    /// Why the model stopped generating, when reported by the provider
+    pub finish_reason: Option<FinishReason>,
+    /// Safety information, when reported by the provider
+    pub safety: Option<SafetyInfo>,
+    /// All candidate responses, in the order returned by the provider
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+    /// The sources cited by the first candidate, such as the results of a web search
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+}
+
+/// A source cited by a response, normalized across providers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct Citation {
+    /// The URL of the source
+    pub url: String,
+    /// The title of the source, when reported by the provider
+    pub title: Option<String>,
+    /// The text of the response supported by the source, when reported by the provider
+    pub text: Option<String>,
+}
+
+/// A single candidate response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Candidate {
+    /// The index of the candidate, as assigned by the provider
+    pub index: u64,
+    /// The text of the candidate
+    pub text: String,
+    /// The reasoning of the candidate, when returned by the model
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    /// Why the model stopped generating this candidate, when reported by the provider
+    pub finish_reason: Option<FinishReason>,
+}
+
+/// The reason a model stopped generating, normalized across providers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum FinishReason {
+    /// The model reached a natural stopping point or a stop sequence
+    Stop,
+    /// The output was truncated by the maximum output token limit
+    Length,
+    /// The prompt or output was blocked by a safety or content filter
+    ContentFilter,
+    /// The model stopped to call a tool
+    ToolCall,
+    /// Any other reason, holding the provider's raw value
+    Other(String),
+}
+
+/// Safety information reported by a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct SafetyInfo {
+    /// The reason the prompt was blocked before generation, if it was
+    pub prompt_block_reason: Option<String>,
+    /// The safety ratings of the prompt
+    pub prompt_ratings: Vec<SafetyRating>,
+    /// The safety ratings of the response
+    pub response_ratings: Vec<SafetyRating>,
+}
+
+impl SafetyInfo {
+    /// Returns `true` if the prompt or any part of the response was blocked.
+    #[must_use]
+    pub fn is_blocked(&self) -> bool {
+        self.prompt_block_reason.is_some()
+            || self
+                .prompt_ratings
+                .iter()
+                .chain(&self.response_ratings)
+                .any(|rating| rating.blocked)
+    }
+}
+
+/// The rating of some content for a single harm category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct SafetyRating {
+    /// The harm category, as named by the provider
+    pub category: String,
+    /// The probability of harm, as named by the provider
+    pub probability: String,
+    /// Whether the content was blocked because of this rating
+    pub blocked: bool,
+}
+
+/// The modality of some content, used to break down token usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Modality {
+    Text,
+    Image,
+    Audio,
+    Video,
+    Document,
+    /// A modality the provider did not specify or that is not known to LatchLM
+    Unspecified,
 }
 
 /// Token usage information returned by LLM providers.
+///
+/// Usage from multiple calls can be summed with `+`, `+=` or [`Iterator::sum`].
+/// Counts that are missing in both operands stay `None`, and sums saturate at `u64::MAX`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct TokenUsage {
-    /// Number of tokens in the input prompt
+    /// Number of tokens in the input prompt, including cached tokens
     pub input_tokens: Option<u64>,
-    /// Number of tokens in the output response
+    /// Number of input tokens read from the provider's cache
+    pub cached_input_tokens: Option<u64>,
+    /// Number of tokens in the output response, including reasoning tokens
     pub output_tokens: Option<u64>,
+    /// Number of tokens spent on reasoning
+    pub reasoning_tokens: Option<u64>,
     /// Total tokens used during the interaction
     pub total_tokens: Option<u64>,
+    /// Number of input tokens per modality
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub input_tokens_by_modality: BTreeMap<Modality, u64>,
+    /// Number of output tokens per modality
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub output_tokens_by_modality: BTreeMap<Modality, u64>,
+}
+
+fn add_counts(lhs: Option<u64>, rhs: Option<u64>) -> Option<u64> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs.saturating_add(rhs)),
+        (lhs, rhs) => lhs.or(rhs),
+    }
+}
+
+fn add_modalities(lhs: &mut BTreeMap<Modality, u64>, rhs: BTreeMap<Modality, u64>) {
+    for (modality, count) in rhs {
+        let total = lhs.entry(modality).or_default();
+        *total = total.saturating_add(count);
+    }
+}
+
+impl AddAssign for TokenUsage {
+    fn add_assign(&mut self, rhs: Self) {
+        self.input_tokens = add_counts(self.input_tokens, rhs.input_tokens);
+        self.cached_input_tokens = add_counts(self.cached_input_tokens, rhs.cached_input_tokens);
+        self.output_tokens = add_counts(self.output_tokens, rhs.output_tokens);
+        self.reasoning_tokens = add_counts(self.reasoning_tokens, rhs.reasoning_tokens);
+        self.total_tokens = add_counts(self.total_tokens, rhs.total_tokens);
+        add_modalities(
+            &mut self.input_tokens_by_modality,
+            rhs.input_tokens_by_modality,
+        );
+        add_modalities(
+            &mut self.output_tokens_by_modality,
+            rhs.output_tokens_by_modality,
+        );
+    }
+}
+
+impl Add for TokenUsage {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl Sum for TokenUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// How a long-running operation, such as a background response, is polled until it finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollOptions {
+    /// The delay between two status checks
+    pub interval: Duration,
+    /// How long to wait before giving up, without limit if `None`
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            timeout: None,
+        }
+    }
 }
 
 /// A trait representing an LLM API provider.
@@ -246,3 +551,83 @@ where
         (**self).send_streaming(model, request)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_usage_add() {
+        let first = TokenUsage {
+            input_tokens: Some(10),
+            cached_input_tokens: Some(4),
+            output_tokens: Some(5),
+            total_tokens: Some(15),
+            input_tokens_by_modality: BTreeMap::from([(Modality::Text, 6), (Modality::Image, 4)]),
+            ..Default::default()
+        };
+        let second = TokenUsage {
+            input_tokens: Some(1),
+            output_tokens: Some(2),
+            reasoning_tokens: Some(7),
+            total_tokens: Some(10),
+            input_tokens_by_modality: BTreeMap::from([(Modality::Text, 1)]),
+            ..Default::default()
+        };
+
+        let sum = first + second;
+
+        assert_eq!(sum.input_tokens, Some(11));
+        assert_eq!(sum.cached_input_tokens, Some(4));
+        assert_eq!(sum.output_tokens, Some(7));
+        assert_eq!(sum.reasoning_tokens, Some(7));
+        assert_eq!(sum.total_tokens, Some(25));
+        assert_eq!(
+            sum.input_tokens_by_modality,
+            BTreeMap::from([(Modality::Text, 7), (Modality::Image, 4)])
+        );
+        assert!(sum.output_tokens_by_modality.is_empty());
+    }
+
+    #[test]
+    fn test_token_usage_sum() {
+        let usage = TokenUsage {
+            total_tokens: Some(3),
+            ..Default::default()
+        };
+
+        let sum: TokenUsage = std::iter::repeat_n(usage, 3).sum();
+        assert_eq!(sum.total_tokens, Some(9));
+        assert_eq!(sum.input_tokens, None);
+
+        let empty: TokenUsage = std::iter::empty().sum();
+        assert_eq!(empty, TokenUsage::default());
+    }
+
+    #[test]
+    fn test_ai_response_deserializes_without_candidates() {
+        let json = r#"{"text":"Hello","token_usage":{"input_tokens":1,"output_tokens":2,"total_tokens":3}}"#;
+
+        let response: AiResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.text, "Hello");
+        assert!(response.candidates.is_empty());
+        assert!(response.citations.is_empty());
+    }
+
+    #[test]
+    fn test_token_usage_add_saturates() {
+        let usage = TokenUsage {
+            total_tokens: Some(u64::MAX),
+            input_tokens_by_modality: BTreeMap::from([(Modality::Text, u64::MAX)]),
+            ..Default::default()
+        };
+
+        let sum = usage.clone() + usage;
+        assert_eq!(sum.total_tokens, Some(u64::MAX));
+        assert_eq!(
+            sum.input_tokens_by_modality,
+            BTreeMap::from([(Modality::Text, u64::MAX)])
+        );
+    }
+}
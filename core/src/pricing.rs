@@ -0,0 +1,243 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Model pricing and cost estimation.
+//!
+//! A [`PricingTable`] maps model identifiers to their [`ModelPricing`] and estimates the
+//! cost of a request from its [`TokenUsage`]. Provider crates ship tables for their
+//! built-in models, which can be merged, overridden or replaced by a table loaded from a
+//! JSON file.
+
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AiModel, Result, TokenUsage};
+
+/// The price of a model, in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct ModelPricing {
+    /// Price of uncached input tokens
+    pub input: f64,
+    /// Price of cached input tokens, charged at the input price if not set
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+    /// Price of output tokens
+    pub output: f64,
+    /// Price of reasoning tokens, charged at the output price if not set
+    #[serde(default)]
+    pub reasoning: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Creates a new pricing from input and output prices per million tokens.
+    #[must_use]
+    pub const fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            cached_input: None,
+            output,
+            reasoning: None,
+        }
+    }
+
+    /// Sets the price of cached input tokens per million tokens.
+    #[must_use]
+    pub const fn with_cached_input(mut self, cached_input: f64) -> Self {
+        self.cached_input = Some(cached_input);
+        self
+    }
+
+    /// Sets the price of reasoning tokens per million tokens.
+    #[must_use]
+    pub const fn with_reasoning(mut self, reasoning: f64) -> Self {
+        self.reasoning = Some(reasoning);
+        self
+    }
+
+    /// Estimates the cost of `usage` in US dollars.
+    ///
+    /// Cached tokens are charged at the cached input price and reasoning tokens at the
+    /// reasoning price, the remaining input and output tokens at the regular prices.
+    /// Missing counts are treated as zero.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let input = usage.input_tokens.unwrap_or_default();
+        let cached = usage.cached_input_tokens.unwrap_or_default().min(input);
+        let output = usage.output_tokens.unwrap_or_default();
+        let reasoning = usage.reasoning_tokens.unwrap_or_default().min(output);
+
+        let cost = (input - cached) as f64 * self.input
+            + cached as f64 * self.cached_input.unwrap_or(self.input)
+            + (output - reasoning) as f64 * self.output
+            + reasoning as f64 * self.reasoning.unwrap_or(self.output);
+
+        cost / 1_000_000.0
+    }
+}
+
+/// A table of model prices keyed by model id.
+///
+/// Keys are the technical identifiers used in API requests, as returned by
+/// [`AiModel::as_ref`] and [`ModelId::id`].
+///
+/// # Example
+/// ```
+/// use latchlm_core::{ModelPricing, PricingTable, TokenUsage};
+///
+/// let mut table = PricingTable::new();
+/// table.insert("my-model", ModelPricing::new(1.0, 4.0).with_cached_input(0.25));
+///
+/// let usage = TokenUsage {
+///     input_tokens: Some(1_000_000),
+///     cached_input_tokens: Some(1_000_000),
+///     output_tokens: Some(500_000),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(table.cost("my-model", &usage), Some(2.25));
+/// ```
+///
+/// [`ModelId::id`]: crate::ModelId::id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct PricingTable {
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl PricingTable {
+    /// Creates an empty pricing table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a pricing table from a JSON file mapping model ids to prices.
+    ///
+    /// ```json
+    /// {
+    ///     "gemini-2.5-flash": { "input": 0.3, "cached_input": 0.03, "output": 2.5 }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid JSON pricing table.
+    pub fn from_json_path(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Sets the price of a model, replacing any previous price.
+    pub fn insert(&mut self, model_id: impl Into<String>, pricing: ModelPricing) {
+        self.prices.insert(model_id.into(), pricing);
+    }
+
+    /// Adds all prices of `other`, overriding the prices of models present in both tables.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.prices.extend(other.prices);
+        self
+    }
+
+    /// Returns the price of a model, if known.
+    #[must_use]
+    pub fn get(&self, model_id: &str) -> Option<&ModelPricing> {
+        self.prices.get(model_id)
+    }
+
+    /// Estimates the cost of `usage` for a model, or `None` if the model has no price.
+    #[must_use]
+    pub fn cost(&self, model_id: &str, usage: &TokenUsage) -> Option<f64> {
+        self.get(model_id).map(|pricing| pricing.cost(usage))
+    }
+
+    /// Estimates the cost of `usage` for `model`, or `None` if the model has no price.
+    #[must_use]
+    pub fn model_cost(&self, model: &dyn AiModel, usage: &TokenUsage) -> Option<f64> {
+        self.cost(model.as_ref(), usage)
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, ModelPricing)> for PricingTable {
+    fn from_iter<T: IntoIterator<Item = (S, ModelPricing)>>(iter: T) -> Self {
+        Self {
+            prices: iter
+                .into_iter()
+                .map(|(model_id, pricing)| (model_id.into(), pricing))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_with_cached_and_reasoning_tokens() {
+        let pricing = ModelPricing::new(2.0, 8.0)
+            .with_cached_input(0.5)
+            .with_reasoning(10.0);
+
+        let usage = TokenUsage {
+            input_tokens: Some(3_000_000),
+            cached_input_tokens: Some(1_000_000),
+            output_tokens: Some(2_000_000),
+            reasoning_tokens: Some(1_000_000),
+            ..Default::default()
+        };
+
+        // 2 * 2.0 + 1 * 0.5 + 1 * 8.0 + 1 * 10.0
+        assert!((pricing.cost(&usage) - 22.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_cost_defaults_to_regular_prices() {
+        let pricing = ModelPricing::new(1.0, 2.0);
+
+        let usage = TokenUsage {
+            input_tokens: Some(500_000),
+            cached_input_tokens: Some(500_000),
+            output_tokens: Some(500_000),
+            reasoning_tokens: Some(500_000),
+            ..Default::default()
+        };
+
+        assert!((pricing.cost(&usage) - 1.5).abs() < f64::EPSILON);
+        assert!(pricing.cost(&TokenUsage::default()).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_table_from_json() {
+        let table: PricingTable = serde_json::from_value(serde_json::json!({
+            "model-a": {"input": 1.0, "output": 2.0},
+            "model-b": {"input": 3.0, "cached_input": 0.3, "output": 6.0}
+        }))
+        .unwrap();
+
+        assert_eq!(table.get("model-a"), Some(&ModelPricing::new(1.0, 2.0)));
+        assert_eq!(
+            table.get("model-b"),
+            Some(&ModelPricing::new(3.0, 6.0).with_cached_input(0.3))
+        );
+        assert_eq!(table.cost("model-c", &TokenUsage::default()), None);
+    }
+
+    #[test]
+    fn test_table_merge_overrides() {
+        let defaults = PricingTable::from_iter([
+            ("model-a", ModelPricing::new(1.0, 2.0)),
+            ("model-b", ModelPricing::new(3.0, 4.0)),
+        ]);
+        let overrides = PricingTable::from_iter([("model-a", ModelPricing::new(0.5, 1.0))]);
+
+        let table = defaults.merge(overrides);
+
+        assert_eq!(table.get("model-a"), Some(&ModelPricing::new(0.5, 1.0)));
+        assert_eq!(table.get("model-b"), Some(&ModelPricing::new(3.0, 4.0)));
+    }
+}
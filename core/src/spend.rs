@@ -0,0 +1,386 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Spend tracking and budget enforcement.
+//!
+//! [`SpendTracker`] wraps any [`AiProvider`] and records the estimated cost of every
+//! request, per model and per tag, using a [`PricingTable`].
+
+use std::{
+    collections::HashMap,
+    future::ready,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures::{StreamExt, stream::BoxStream};
+
+use crate::{
+    AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, Error, PricingTable, Result, TokenUsage,
+};
+
+/// A snapshot of the spend recorded by a [`SpendTracker`].
+///
+/// Costs are estimates in US dollars.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SpendReport {
+    /// The total estimated cost of all requests
+    pub total: f64,
+    /// The estimated cost per model id
+    pub by_model: HashMap<String, f64>,
+    /// The estimated cost per tag
+    pub by_tag: HashMap<String, f64>,
+    /// The token usage per model id, including models without a price
+    pub usage_by_model: HashMap<String, TokenUsage>,
+}
+
+#[derive(Debug)]
+struct Ledger {
+    pricing: PricingTable,
+    budget: Option<f64>,
+    report: SpendReport,
+}
+
+impl Ledger {
+    fn record(&mut self, model_id: &str, tag: Option<&str>, usage: TokenUsage) {
+        if let Some(cost) = self.pricing.cost(model_id, &usage) {
+            self.report.total += cost;
+            *self.report.by_model.entry(model_id.to_owned()).or_default() += cost;
+
+            if let Some(tag) = tag {
+                *self.report.by_tag.entry(tag.to_owned()).or_default() += cost;
+            }
+        }
+
+        *self
+            .report
+            .usage_by_model
+            .entry(model_id.to_owned())
+            .or_default() += usage;
+    }
+
+    fn check_budget(&self) -> Result<()> {
+        match self.budget {
+            Some(budget) if self.report.total >= budget => Err(Error::BudgetExceededError {
+                spent: self.report.total,
+                budget,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SharedLedger(Arc<Mutex<Ledger>>);
+
+impl SharedLedger {
+    fn lock(&self) -> MutexGuard<'_, Ledger> {
+        // The ledger holds plain data, so it is still consistent after a panic.
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Records the usage of a streaming response when the stream is dropped.
+struct StreamUsage {
+    ledger: SharedLedger,
+    model_id: String,
+    tag: Option<String>,
+    usage: TokenUsage,
+}
+
+impl StreamUsage {
+    fn observe(&mut self, item: &Result<AiResponse>) {
+        if let Ok(response) = item
+            && response.token_usage != TokenUsage::default()
+        {
+            self.usage = response.token_usage.clone();
+        }
+    }
+}
+
+impl Drop for StreamUsage {
+    fn drop(&mut self) {
+        let usage = std::mem::take(&mut self.usage);
+        self.ledger
+            .lock()
+            .record(&self.model_id, self.tag.as_deref(), usage);
+    }
+}
+
+/// An [`AiProvider`] wrapper that tracks the estimated cost of requests.
+///
+/// Every successful response is priced with the tracker's [`PricingTable`]. Requests to
+/// models without a price still have their token usage recorded but add nothing to the
+/// spend. When a budget is set, requests are refused with
+/// [`Error::BudgetExceededError`] once the total spend reaches it.
+///
+/// Trackers created with [`SpendTracker::tagged`] share the ledger of the tracker they
+/// were created from and additionally attribute their spend to a tag.
+///
+/// # Example
+/// ```no_run
+/// use latchlm_core::{AiModel, AiProvider, AiRequest, PricingTable, SpendTracker};
+///
+/// async fn summarize(
+///     provider: impl AiProvider,
+///     model: &dyn AiModel,
+///     pricing: PricingTable,
+/// ) -> latchlm_core::Result<()> {
+///     let tracker = SpendTracker::new(provider, pricing).with_budget(10.0);
+///     let summaries = tracker.tagged("summaries");
+///
+///     summaries
+///         .send_request(model, AiRequest { text: "Summarize...".into(), ..Default::default() })
+///         .await?;
+///
+///     println!("Spent ${:.4}", tracker.report().total);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SpendTracker<P> {
+    inner: P,
+    ledger: SharedLedger,
+    tag: Option<String>,
+}
+
+impl<P: AiProvider> SpendTracker<P> {
+    /// Wraps `inner`, pricing its requests with `pricing`.
+    pub fn new(inner: P, pricing: PricingTable) -> Self {
+        Self {
+            inner,
+            ledger: SharedLedger(Arc::new(Mutex::new(Ledger {
+                pricing,
+                budget: None,
+                report: SpendReport::default(),
+            }))),
+            tag: None,
+        }
+    }
+
+    /// Sets the maximum total spend, in US dollars, shared by all tagged trackers.
+    #[must_use]
+    pub fn with_budget(self, budget: f64) -> Self {
+        self.ledger.lock().budget = Some(budget);
+        self
+    }
+
+    /// Returns a tracker for the same provider and ledger that attributes spend to `tag`.
+    #[must_use]
+    pub fn tagged(&self, tag: impl Into<String>) -> SpendTracker<&P> {
+        SpendTracker {
+            inner: &self.inner,
+            ledger: self.ledger.clone(),
+            tag: Some(tag.into()),
+        }
+    }
+
+    /// Returns a snapshot of the spend recorded so far.
+    #[must_use]
+    pub fn report(&self) -> SpendReport {
+        self.ledger.lock().report.clone()
+    }
+
+    /// Returns the remaining budget, or `None` if no budget is set.
+    #[must_use]
+    pub fn remaining_budget(&self) -> Option<f64> {
+        let ledger = self.ledger.lock();
+        ledger
+            .budget
+            .map(|budget| (budget - ledger.report.total).max(0.0))
+    }
+
+    /// Returns the wrapped provider.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: AiProvider> AiProvider for SpendTracker<P> {
+    fn send_request(
+        &self,
+        model: &dyn AiModel,
+        request: AiRequest,
+    ) -> BoxFuture<'_, Result<AiResponse>> {
+        if let Err(err) = self.ledger.lock().check_budget() {
+            return Box::pin(ready(Err(err)));
+        }
+
+        let model_id = model.as_ref().to_owned();
+        let response = self.inner.send_request(model, request);
+
+        Box::pin(async move {
+            let response = response.await?;
+
+            self.ledger
+                .lock()
+                .record(&model_id, self.tag.as_deref(), response.token_usage.clone());
+
+            Ok(response)
+        })
+    }
+
+    fn send_streaming(
+        &self,
+        model: &dyn AiModel,
+        request: AiRequest,
+    ) -> BoxStream<'_, Result<AiResponse>> {
+        if let Err(err) = self.ledger.lock().check_budget() {
+            return futures::stream::once(ready(Err(err))).boxed();
+        }
+
+        // Providers report usage once per stream or cumulatively on every chunk,
+        // so only the last reported usage is recorded.
+        let mut usage = StreamUsage {
+            ledger: self.ledger.clone(),
+            model_id: model.as_ref().to_owned(),
+            tag: self.tag.clone(),
+            usage: TokenUsage::default(),
+        };
+
+        self.inner
+            .send_streaming(model, request)
+            .map(move |item| {
+                usage.observe(&item);
+                item
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{ModelId, ModelPricing};
+
+    struct TestModel;
+
+    impl AsRef<str> for TestModel {
+        fn as_ref(&self) -> &str {
+            "test-model"
+        }
+    }
+
+    impl AiModel for TestModel {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn model_id(&self) -> ModelId<'_> {
+            ModelId {
+                id: "test-model".into(),
+                name: "Test Model".into(),
+                ..Default::default()
+            }
+        }
+    }
+
+    struct TestProvider;
+
+    fn response() -> AiResponse {
+        AiResponse {
+            text: "response".into(),
+            token_usage: TokenUsage {
+                input_tokens: Some(1_000_000),
+                output_tokens: Some(1_000_000),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    impl AiProvider for TestProvider {
+        fn send_request(
+            &self,
+            _model: &dyn AiModel,
+            _request: AiRequest,
+        ) -> BoxFuture<'_, Result<AiResponse>> {
+            Box::pin(ready(Ok(response())))
+        }
+
+        fn send_streaming(
+            &self,
+            _model: &dyn AiModel,
+            _request: AiRequest,
+        ) -> BoxStream<'_, Result<AiResponse>> {
+            futures::stream::iter([
+                Ok(AiResponse {
+                    text: "chunk".into(),
+                    ..Default::default()
+                }),
+                Ok(response()),
+            ])
+            .boxed()
+        }
+    }
+
+    fn pricing() -> PricingTable {
+        PricingTable::from_iter([("test-model", ModelPricing::new(1.0, 2.0))])
+    }
+
+    #[tokio::test]
+    async fn test_records_spend_per_model_and_tag() {
+        let tracker = SpendTracker::new(TestProvider, pricing());
+
+        tracker
+            .send_request(&TestModel, AiRequest::default())
+            .await
+            .unwrap();
+        tracker
+            .tagged("feature")
+            .send_request(&TestModel, AiRequest::default())
+            .await
+            .unwrap();
+
+        let report = tracker.report();
+        assert!((report.total - 6.0).abs() < f64::EPSILON);
+        assert!((report.by_model["test-model"] - 6.0).abs() < f64::EPSILON);
+        assert!((report.by_tag["feature"] - 3.0).abs() < f64::EPSILON);
+        assert_eq!(
+            report.usage_by_model["test-model"].input_tokens,
+            Some(2_000_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_records_streaming_spend() {
+        let tracker = SpendTracker::new(TestProvider, pricing());
+
+        let chunks = tracker
+            .send_streaming(&TestModel, AiRequest::default())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(chunks.len(), 2);
+        assert!((tracker.report().total - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_requests_over_budget() {
+        let tracker = SpendTracker::new(TestProvider, pricing()).with_budget(5.0);
+
+        for _ in 0..2 {
+            tracker
+                .send_request(&TestModel, AiRequest::default())
+                .await
+                .unwrap();
+        }
+
+        let err = tracker
+            .tagged("feature")
+            .send_request(&TestModel, AiRequest::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::BudgetExceededError { .. }));
+
+        let mut stream = tracker.send_streaming(&TestModel, AiRequest::default());
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(Error::BudgetExceededError { .. }))
+        ));
+        assert_eq!(tracker.remaining_budget(), Some(0.0));
+    }
+}
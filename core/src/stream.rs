@@ -0,0 +1,429 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Helpers for consuming response streams.
+//!
+//! This module provides the [`ResponseStreamExt`] extension trait, implemented for every
+//! stream returned by [`AiProvider::send_streaming`], to drive a stream into a sink with
+//! bounded buffering and to fan a single stream out to multiple consumers.
+//!
+//! [`AiProvider::send_streaming`]: crate::AiProvider::send_streaming
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+
+use futures::{SinkExt, Stream, StreamExt, future::Either, stream::BoxStream};
+
+use crate::{AiResponse, Error, Result};
+
+#[cfg(feature = "tokio")]
+use crate::TokenUsage;
+
+/// Extension methods for streams of [`AiResponse`] chunks.
+pub trait ResponseStreamExt<'a>: Stream<Item = Result<AiResponse>> + Send + Sized + 'a {
+    /// Forwards every item of the stream, errors included, into a bounded `tokio` channel.
+    ///
+    /// The stream is only polled when the channel has capacity, so a slow receiver
+    /// slows down consumption of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChannelClosedError`] if the receiver is dropped before the
+    /// stream is exhausted.
+    ///
+    /// # Feature
+    /// Requires the `tokio` feature flag.
+    #[cfg(feature = "tokio")]
+    fn forward_to(
+        self,
+        sender: &tokio::sync::mpsc::Sender<Result<AiResponse>>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let mut stream = std::pin::pin!(self);
+
+            while let Some(item) = stream.next().await {
+                sender
+                    .send(item)
+                    .await
+                    .map_err(|_| Error::ChannelClosedError)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Writes the text of every chunk to an [`AsyncWrite`], flushing after each chunk.
+    ///
+    /// Returns the last token usage reported by the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error yielded by the stream, or an [`Error::IoError`] if
+    /// writing to `writer` fails.
+    ///
+    /// # Feature
+    /// Requires the `tokio` feature flag.
+    ///
+    /// [`AsyncWrite`]: tokio::io::AsyncWrite
+    #[cfg(feature = "tokio")]
+    fn write_to<W>(self, writer: &mut W) -> impl Future<Output = Result<TokenUsage>> + Send
+    where
+        W: tokio::io::AsyncWrite + Unpin + Send,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        async move {
+            let mut stream = std::pin::pin!(self);
+            let mut token_usage = TokenUsage::default();
+
+            while let Some(item) = stream.next().await {
+                let response = item?;
+
+                writer.write_all(response.text.as_bytes()).await?;
+                writer.flush().await?;
+
+                if response.token_usage != TokenUsage::default() {
+                    token_usage = response.token_usage;
+                }
+            }
+
+            Ok(token_usage)
+        }
+    }
+
+    /// Calls `callback` for every chunk, buffering at most `capacity` chunks.
+    ///
+    /// The stream is read concurrently with the callback, so network reads are not held up
+    /// by a slow callback until `capacity` chunks are waiting to be processed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error yielded by the stream or returned by `callback`.
+    fn for_each_buffered<F, Fut>(
+        self,
+        capacity: usize,
+        mut callback: F,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        F: FnMut(AiResponse) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<()>> + Send + 'a,
+    {
+        async move {
+            let (mut sender, mut receiver) = futures::channel::mpsc::channel(capacity);
+
+            let producer = async move {
+                let mut stream = std::pin::pin!(self);
+
+                while let Some(item) = stream.next().await {
+                    if sender.send(item).await.is_err() {
+                        break;
+                    }
+                }
+            };
+
+            let consumer = async move {
+                while let Some(item) = receiver.next().await {
+                    callback(item?).await?;
+                }
+
+                Ok(())
+            };
+
+            // Stop reading the source as soon as the consumer gives up.
+            match futures::future::select(std::pin::pin!(producer), std::pin::pin!(consumer)).await
+            {
+                Either::Left(((), consumer)) => consumer.await,
+                Either::Right((result, _)) => result,
+            }
+        }
+    }
+
+    /// Splits the stream into two streams that both yield every item.
+    ///
+    /// Each branch buffers at most `capacity` items that the other branch has already
+    /// read. When a branch falls behind by that many items, the other branch stops
+    /// reading from the source until it catches up. Dropping a branch lets the other
+    /// one proceed without buffering.
+    ///
+    /// Errors are delivered to both branches. Errors that cannot be cloned are delivered
+    /// to the second reader as an [`Error::StreamError`] carrying the original message.
+    fn tee(self, capacity: usize) -> (Tee<'a>, Tee<'a>) {
+        let shared = Arc::new(Mutex::new(TeeShared {
+            source: self.boxed(),
+            capacity: capacity.max(1),
+            done: false,
+            branches: Default::default(),
+        }));
+
+        (
+            Tee {
+                shared: Arc::clone(&shared),
+                index: 0,
+            },
+            Tee { shared, index: 1 },
+        )
+    }
+}
+
+impl<'a, S> ResponseStreamExt<'a> for S where S: Stream<Item = Result<AiResponse>> + Send + 'a {}
+
+#[derive(Default)]
+struct TeeBranch {
+    buffer: VecDeque<Result<AiResponse>>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+struct TeeShared<'a> {
+    source: BoxStream<'a, Result<AiResponse>>,
+    capacity: usize,
+    done: bool,
+    branches: [TeeBranch; 2],
+}
+
+/// One branch of a stream split with [`ResponseStreamExt::tee`].
+pub struct Tee<'a> {
+    shared: Arc<Mutex<TeeShared<'a>>>,
+    index: usize,
+}
+
+impl<'a> Tee<'a> {
+    fn lock(&self) -> MutexGuard<'_, TeeShared<'a>> {
+        // A panic while holding the lock cannot leave the state half-updated,
+        // so a poisoned lock is still safe to use.
+        self.shared
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for Tee<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tee").field("index", &self.index).finish()
+    }
+}
+
+impl Stream for Tee<'_> {
+    type Item = Result<AiResponse>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let (own, other) = (this.index, 1 - this.index);
+        let mut shared = this.lock();
+        let shared = &mut *shared;
+
+        if let Some(item) = shared.branches[own].buffer.pop_front() {
+            if let Some(waker) = shared.branches[other].waker.take() {
+                waker.wake();
+            }
+
+            return Poll::Ready(Some(item));
+        }
+
+        if shared.done {
+            return Poll::Ready(None);
+        }
+
+        let other_branch = &shared.branches[other];
+        if !other_branch.closed && other_branch.buffer.len() >= shared.capacity {
+            shared.branches[own].waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        match shared.source.poll_next_unpin(cx) {
+            Poll::Ready(Some(item)) => {
+                let other_branch = &mut shared.branches[other];
+
+                if !other_branch.closed {
+                    other_branch.buffer.push_back(duplicate(&item));
+
+                    if let Some(waker) = other_branch.waker.take() {
+                        waker.wake();
+                    }
+                }
+
+                Poll::Ready(Some(item))
+            }
+            Poll::Ready(None) => {
+                shared.done = true;
+
+                if let Some(waker) = shared.branches[other].waker.take() {
+                    waker.wake();
+                }
+
+                Poll::Ready(None)
+            }
+            Poll::Pending => {
+                shared.branches[own].waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Tee<'_> {
+    fn drop(&mut self) {
+        let other = 1 - self.index;
+        let mut shared = self.lock();
+
+        shared.branches[self.index].closed = true;
+        shared.branches[self.index].buffer.clear();
+
+        if let Some(waker) = shared.branches[other].waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+fn duplicate(item: &Result<AiResponse>) -> Result<AiResponse> {
+    match item {
+        Ok(response) => Ok(response.clone()),
+        Err(Error::ApiError { status, message }) => Err(Error::ApiError {
+            status: *status,
+            message: message.clone(),
+        }),
+        Err(Error::InvalidModelError(model)) => Err(Error::InvalidModelError(model.clone())),
+        Err(Error::ProviderError { provider, error }) => Err(Error::ProviderError {
+            provider: provider.clone(),
+            error: error.clone(),
+        }),
+        Err(Error::StreamError(message)) => Err(Error::StreamError(message.clone())),
+        Err(err) => Err(Error::StreamError(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::TokenUsage;
+
+    fn chunk(text: &str) -> Result<AiResponse> {
+        Ok(AiResponse {
+            text: text.to_owned(),
+            ..Default::default()
+        })
+    }
+
+    fn source() -> BoxStream<'static, Result<AiResponse>> {
+        futures::stream::iter(vec![
+            chunk("Hello"),
+            chunk(", "),
+            Ok(AiResponse {
+                text: "world".to_owned(),
+                token_usage: TokenUsage {
+                    input_tokens: Some(1),
+                    output_tokens: Some(3),
+                    total_tokens: Some(4),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        ])
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn test_forward_to() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+
+        let producer = async move { source().forward_to(&sender).await };
+        let consumer = async move {
+            let mut texts = vec![];
+            while let Some(item) = receiver.recv().await {
+                texts.push(item.unwrap().text);
+            }
+            texts
+        };
+
+        let (result, received) = tokio::join!(producer, consumer);
+
+        result.unwrap();
+        assert_eq!(received.concat(), "Hello, world");
+    }
+
+    #[tokio::test]
+    async fn test_forward_to_closed_channel() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        drop(receiver);
+
+        let err = source().forward_to(&sender).await.unwrap_err();
+        assert!(matches!(err, Error::ChannelClosedError));
+    }
+
+    #[tokio::test]
+    async fn test_write_to() {
+        let mut output = Vec::new();
+
+        let usage = source().write_to(&mut output).await.unwrap();
+
+        assert_eq!(output, b"Hello, world");
+        assert_eq!(usage.total_tokens, Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_for_each_buffered_propagates_errors() {
+        let stream = futures::stream::iter(vec![
+            chunk("a"),
+            Err(Error::InvalidModelError("model".into())),
+            chunk("b"),
+        ]);
+
+        let mut seen = vec![];
+        let err = stream
+            .for_each_buffered(1, |response| {
+                seen.push(response.text);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::InvalidModelError(_)));
+        assert_eq!(seen, ["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_tee_yields_every_item_to_both_branches() {
+        let (left, right) = source().tee(1);
+
+        let (left, right) = tokio::join!(left.collect::<Vec<_>>(), right.collect::<Vec<_>>());
+
+        let left: String = left.into_iter().map(|r| r.unwrap().text).collect();
+        let right: String = right.into_iter().map(|r| r.unwrap().text).collect();
+        assert_eq!(left, "Hello, world");
+        assert_eq!(right, "Hello, world");
+    }
+
+    #[tokio::test]
+    async fn test_tee_after_branch_dropped() {
+        let (left, right) = source().tee(1);
+        drop(right);
+
+        let left: String = left.map(|r| r.unwrap().text).collect().await;
+        assert_eq!(left, "Hello, world");
+    }
+
+    #[tokio::test]
+    async fn test_tee_duplicates_errors() {
+        let stream = futures::stream::iter(vec![Err(Error::ApiError {
+            status: 429,
+            message: "rate limited".into(),
+        })]);
+        let (left, right) = stream.tee(1);
+
+        let (left, right) = tokio::join!(left.collect::<Vec<_>>(), right.collect::<Vec<_>>());
+
+        for items in [left, right] {
+            assert!(matches!(
+                items.as_slice(),
+                [Err(Error::ApiError { status: 429, .. })]
+            ));
+        }
+    }
+}
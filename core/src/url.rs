@@ -0,0 +1,154 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! URL helpers shared by the provider clients.
+
+use reqwest::Url;
+
+use crate::{Error, Result};
+
+/// Joins an endpoint `path` onto a provider's `base` URL, keeping the path prefix of `base`.
+///
+/// [`Url::join`] drops the last segment of a base without a trailing slash, and the whole
+/// base path when `path` starts with a slash. This helper treats `base` as a directory
+/// instead, so `https://proxy.example.com/gemini` joined with `v1beta/models` gives
+/// `https://proxy.example.com/gemini/v1beta/models`.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidUrlError`] if `base` cannot be a base URL, such as a `mailto:` URL.
+pub fn join_url(base: &Url, path: &str) -> Result<Url> {
+    if base.cannot_be_a_base() {
+        return Err(Error::InvalidUrlError(base.to_string()));
+    }
+
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        let path = format!("{}/", base.path());
+        base.set_path(&path);
+    }
+
+    base.join(path.trim_start_matches('/'))
+        .map_err(|err| Error::InvalidUrlError(format!("{base}{path}: {err}")))
+}
+
+/// Appends `segments` to a provider's `base` URL, each as a single percent-encoded path
+/// segment, keeping the path prefix of `base`.
+///
+/// Use this instead of [`join_url`] when a segment comes from the caller, such as a resource
+/// id: a `/`, `?` or `#` in a segment is encoded instead of reaching another endpoint or
+/// changing the query.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidUrlError`] if `base` cannot be a base URL, or if a segment is
+/// empty, `.` or `..`.
+pub fn join_url_segments(base: &Url, segments: &[&str]) -> Result<Url> {
+    if let Some(segment) = segments
+        .iter()
+        .find(|segment| matches!(**segment, "" | "." | ".."))
+    {
+        return Err(Error::InvalidUrlError(format!(
+            "{base}: invalid path segment {segment:?}"
+        )));
+    }
+
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|()| Error::InvalidUrlError(base.to_string()))?
+        .pop_if_empty()
+        .extend(segments);
+
+    Ok(url)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_url_preserves_prefix() {
+        let cases = [
+            (
+                "https://example.com",
+                "v1/models",
+                "https://example.com/v1/models",
+            ),
+            (
+                "https://example.com/",
+                "/v1/models",
+                "https://example.com/v1/models",
+            ),
+            (
+                "https://example.com/api",
+                "models",
+                "https://example.com/api/models",
+            ),
+            (
+                "https://example.com/api/",
+                "/models",
+                "https://example.com/api/models",
+            ),
+            (
+                "https://example.com/api/v1",
+                "models/gemini:generate?alt=sse",
+                "https://example.com/api/v1/models/gemini:generate?alt=sse",
+            ),
+        ];
+
+        for (base, path, expected) in cases {
+            let url = join_url(&Url::parse(base).unwrap(), path).unwrap();
+            assert_eq!(url.as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_join_url_rejects_invalid_base() {
+        let base = Url::parse("mailto:user@example.com").unwrap();
+
+        assert!(matches!(
+            join_url(&base, "models"),
+            Err(Error::InvalidUrlError(_))
+        ));
+    }
+
+    #[test]
+    fn test_join_url_segments_encodes_segments() {
+        let cases = [
+            (
+                "https://example.com/v1",
+                ["responses", "resp_1"],
+                "https://example.com/v1/responses/resp_1",
+            ),
+            (
+                "https://example.com/v1/",
+                ["responses", "../files"],
+                "https://example.com/v1/responses/..%2Ffiles",
+            ),
+            (
+                "https://example.com",
+                ["responses", "id?stream=true#x"],
+                "https://example.com/responses/id%3Fstream=true%23x",
+            ),
+        ];
+
+        for (base, segments, expected) in cases {
+            let url = join_url_segments(&Url::parse(base).unwrap(), &segments).unwrap();
+            assert_eq!(url.as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_join_url_segments_rejects_dot_segments() {
+        let base = Url::parse("https://example.com/v1/").unwrap();
+
+        for segment in ["", ".", ".."] {
+            assert!(matches!(
+                join_url_segments(&base, &["responses", segment]),
+                Err(Error::InvalidUrlError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,306 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Context caching, for prompts that reuse large system instructions or documents.
+//!
+//! A cached content is created once with [`Gemini::create_cached_content`] and referenced by
+//! name in the [`GeminiRequestOptions::cached_content`] of later requests, which are then
+//! billed the cheaper cached input rate for the cached tokens. It is deleted by the API when
+//! its time to live elapses.
+//!
+//! [`GeminiRequestOptions::cached_content`]: crate::GeminiRequestOptions::cached_content
+
+use std::time::Duration;
+
+use latchlm_core::{
+    Error, Result, error_for_status, join_url, join_url_segments, parse_json, send_authenticated,
+};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{Content, Gemini, GeminiModel, Text};
+
+/// The content to cache and how long to keep it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CachedContentRequest {
+    /// A name to tell the cached content apart in listings
+    pub display_name: Option<String>,
+    /// The system instruction of the requests using the cache
+    pub system_instruction: Option<String>,
+    /// The user messages to cache, such as documents
+    pub contents: Vec<String>,
+    /// How long the cached content is kept, one hour if `None`
+    pub ttl: Option<Duration>,
+}
+
+/// The body of a `cachedContents.create` request.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateCachedContent {
+    model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ttl: Option<String>,
+}
+
+impl CreateCachedContent {
+    fn new(model: &GeminiModel, request: CachedContentRequest) -> Self {
+        let content = |text, role: Option<&str>| Content {
+            parts: vec![Text {
+                text,
+                ..Default::default()
+            }],
+            role: role.map(str::to_owned),
+        };
+
+        Self {
+            model: format!("models/{}", model.as_ref()),
+            display_name: request.display_name,
+            contents: request
+                .contents
+                .into_iter()
+                .map(|text| content(text, Some("user")))
+                .collect(),
+            system_instruction: request.system_instruction.map(|text| content(text, None)),
+            ttl: request.ttl.map(format_ttl),
+        }
+    }
+}
+
+/// Formats a time to live as a protobuf duration, e.g. `300s`.
+fn format_ttl(duration: Duration) -> String {
+    format!("{}s", duration.as_secs_f64())
+}
+
+/// A cached content, as stored by the Gemini API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedContent {
+    /// The resource name of the cached content, e.g. `cachedContents/abc123`
+    pub name: String,
+    /// The resource name of the model the cache was created for, e.g. `models/gemini-2.5-flash`
+    pub model: Option<String>,
+    pub display_name: Option<String>,
+    /// The creation time, as an RFC 3339 timestamp
+    pub create_time: Option<String>,
+    /// The last update time, as an RFC 3339 timestamp
+    pub update_time: Option<String>,
+    /// The time the cached content is deleted, as an RFC 3339 timestamp
+    pub expire_time: Option<String>,
+    pub usage_metadata: Option<CachedContentUsage>,
+}
+
+/// The size of a cached content.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedContentUsage {
+    /// The number of cached tokens
+    #[serde(default)]
+    pub total_token_count: u64,
+}
+
+/// A page of the list of cached contents.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct CachedContentsList {
+    #[serde(default)]
+    cached_contents: Vec<CachedContent>,
+    next_page_token: Option<String>,
+}
+
+impl Gemini {
+    /// Caches content for the requests made to `model`, and returns the cached content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, such as for content below the minimum cacheable size of the model, or the
+    /// response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn create_cached_content(
+        &self,
+        model: GeminiModel,
+        request: CachedContentRequest,
+    ) -> Result<CachedContent> {
+        let url = join_url(&self.base_url, "v1beta/cachedContents")?;
+        let payload = CreateCachedContent::new(&model, request);
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                .json(&payload)
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Lists the cached contents of the project. All pages of the listing are fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn list_cached_contents(&self) -> Result<Vec<CachedContent>> {
+        let url = join_url(&self.base_url, "v1beta/cachedContents")?;
+
+        let mut cached_contents = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("pageSize", "1000")];
+            if let Some(page_token) = &page_token {
+                query.push(("pageToken", page_token.as_str()));
+            }
+
+            let response = send_authenticated(&*self.credentials, |api_key| {
+                self.client
+                    .get(url.clone())
+                    .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                    .query(&query)
+            })
+            .await?;
+
+            let page: CachedContentsList = parse_json(response).await?;
+            cached_contents.extend(page.cached_contents);
+
+            match page.next_page_token {
+                Some(next_page_token) if !next_page_token.is_empty() => {
+                    page_token = Some(next_page_token);
+                }
+                _ => return Ok(cached_contents),
+            }
+        }
+    }
+
+    /// Sets the time to live of a cached content, counted from now, and returns the updated
+    /// cached content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if `name` does not start with `cachedContents/`, the HTTP request
+    /// fails, the API returns a non-success status code, such as for an expired cached
+    /// content, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn update_cached_content_ttl(
+        &self,
+        name: &str,
+        ttl: Duration,
+    ) -> Result<CachedContent> {
+        let url = cached_content_url(&self.base_url, name)?;
+        let payload = serde_json::json!({ "ttl": format_ttl(ttl) });
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .patch(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                .query(&[("updateMask", "ttl")])
+                .json(&payload)
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Deletes a cached content before its time to live elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if `name` does not start with `cachedContents/`, the HTTP request
+    /// fails or the API returns a non-success status code, such as for an unknown cached
+    /// content.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_cached_content(&self, name: &str) -> Result<()> {
+        let url = cached_content_url(&self.base_url, name)?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .delete(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+        })
+        .await?;
+
+        error_for_status(response).await?;
+
+        Ok(())
+    }
+}
+
+/// Returns the URL of the cached content `name`, such as `cachedContents/abc123`.
+fn cached_content_url(base_url: &reqwest::Url, name: &str) -> Result<reqwest::Url> {
+    let id = name.strip_prefix("cachedContents/").ok_or_else(|| {
+        Error::InvalidUrlError(format!(
+            "cached content name {name:?} does not start with cachedContents/"
+        ))
+    })?;
+
+    join_url_segments(base_url, &["v1beta", "cachedContents", id])
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_create_cached_content() {
+        let request = CreateCachedContent::new(
+            &GeminiModel::Flash25,
+            CachedContentRequest {
+                display_name: Some("handbook".into()),
+                system_instruction: Some("Answer from the handbook.".into()),
+                contents: vec!["The handbook".into()],
+                ttl: Some(Duration::from_millis(1500)),
+            },
+        );
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({
+                "model": "models/gemini-2.5-flash",
+                "displayName": "handbook",
+                "contents": [{"parts": [{"text": "The handbook"}], "role": "user"}],
+                "systemInstruction": {"parts": [{"text": "Answer from the handbook."}]},
+                "ttl": "1.5s"
+            })
+        );
+    }
+
+    #[test]
+    fn test_cached_content_url() {
+        let base_url = reqwest::Url::parse("https://example.com/gemini/").unwrap();
+
+        assert_eq!(
+            cached_content_url(&base_url, "cachedContents/handbook")
+                .unwrap()
+                .as_str(),
+            "https://example.com/gemini/v1beta/cachedContents/handbook"
+        );
+        assert_eq!(
+            cached_content_url(&base_url, "cachedContents/../files/x?y")
+                .unwrap()
+                .as_str(),
+            "https://example.com/gemini/v1beta/cachedContents/..%2Ffiles%2Fx%3Fy"
+        );
+
+        for name in [
+            "files/report",
+            "handbook",
+            "cachedContents/",
+            "cachedContents/..",
+        ] {
+            assert!(matches!(
+                cached_content_url(&base_url, name),
+                Err(Error::InvalidUrlError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,394 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! The Files API, for media too large to be sent inline, such as PDFs, videos and audio.
+//!
+//! Files are uploaded with the resumable upload protocol, in chunks read from an
+//! [`AsyncRead`], and referenced in the [`GeminiRequestOptions::files`] of later requests
+//! once they are `ACTIVE`. Uploaded files are deleted by the API after 48 hours.
+//!
+//! [`GeminiRequestOptions::files`]: crate::GeminiRequestOptions::files
+
+use std::time::Instant;
+
+use latchlm_core::{
+    Error, PollOptions, Result, error_for_status, join_url, join_url_segments, parse_json,
+    send_authenticated,
+};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{FileData, Gemini};
+
+/// The size of the chunks of a resumable upload, a multiple of the required 256 KiB.
+const UPLOAD_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// The metadata of a file to upload.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileUpload {
+    /// The MIME type of the file, e.g. `application/pdf`
+    pub mime_type: String,
+    /// A name to tell the file apart in listings
+    pub display_name: Option<String>,
+    /// The size of the file in bytes, if known in advance
+    pub size: Option<u64>,
+}
+
+/// The processing state of an uploaded file.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum FileState {
+    #[default]
+    StateUnspecified,
+    /// The file is being processed and cannot be used yet
+    Processing,
+    /// The file can be used in requests
+    Active,
+    /// The file could not be processed
+    Failed,
+    /// A state that is not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// The error that made the processing of a file fail.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileError {
+    #[serde(default)]
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+}
+
+/// A file uploaded with the Files API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFile {
+    /// The resource name of the file, e.g. `files/abc123`
+    pub name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub mime_type: String,
+    /// The size of the file in bytes, as a decimal string
+    pub size_bytes: Option<String>,
+    /// The creation time, as an RFC 3339 timestamp
+    pub create_time: Option<String>,
+    /// The last update time, as an RFC 3339 timestamp
+    pub update_time: Option<String>,
+    /// The time the file is deleted, as an RFC 3339 timestamp
+    pub expiration_time: Option<String>,
+    /// The base64-encoded SHA-256 hash of the file
+    pub sha256_hash: Option<String>,
+    /// The URI used to reference the file in requests
+    #[serde(default)]
+    pub uri: String,
+    #[serde(default)]
+    pub state: FileState,
+    pub error: Option<FileError>,
+}
+
+impl GeminiFile {
+    /// Returns the size of the file in bytes, if reported.
+    #[must_use]
+    pub fn size(&self) -> Option<u64> {
+        self.size_bytes.as_deref()?.parse().ok()
+    }
+
+    /// Returns the reference to the file used as a part of a prompt.
+    #[must_use]
+    pub fn file_data(&self) -> FileData {
+        FileData {
+            mime_type: self.mime_type.clone(),
+            file_uri: self.uri.clone(),
+        }
+    }
+}
+
+impl From<&GeminiFile> for FileData {
+    fn from(value: &GeminiFile) -> Self {
+        value.file_data()
+    }
+}
+
+/// The body of a finished upload.
+#[derive(Deserialize, Debug)]
+struct UploadedFile {
+    file: GeminiFile,
+}
+
+/// A page of the list of uploaded files.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct FilesList {
+    #[serde(default)]
+    files: Vec<GeminiFile>,
+    next_page_token: Option<String>,
+}
+
+/// Reads the next chunk of an upload, which is shorter than [`UPLOAD_CHUNK_SIZE`] only at the
+/// end of `reader`.
+async fn read_chunk<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut chunk = Vec::new();
+    reader
+        .take(UPLOAD_CHUNK_SIZE)
+        .read_to_end(&mut chunk)
+        .await?;
+
+    Ok(chunk)
+}
+
+fn gemini_error(error: impl Into<String>) -> Error {
+    Error::ProviderError {
+        provider: "Gemini".into(),
+        error: error.into(),
+    }
+}
+
+/// Returns the URL of the uploaded file `name`, such as `files/abc123`.
+fn file_url(base_url: &reqwest::Url, name: &str) -> Result<reqwest::Url> {
+    let id = name.strip_prefix("files/").ok_or_else(|| {
+        Error::InvalidUrlError(format!("file name {name:?} does not start with files/"))
+    })?;
+
+    join_url_segments(base_url, &["v1beta", "files", id])
+}
+
+impl Gemini {
+    /// Uploads a file read from `reader` with the resumable upload protocol, and returns it.
+    ///
+    /// The file is read and sent in chunks of 8 MiB. Videos and some other files are
+    /// processed after the upload, so use [`Gemini::wait_for_file`] before using them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if reading `reader` fails, an HTTP request fails, the API returns
+    /// a non-success status code, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, reader)))]
+    pub async fn upload_file<R>(&self, mut reader: R, upload: FileUpload) -> Result<GeminiFile>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        let url = join_url(&self.base_url, "upload/v1beta/files")?;
+        let payload = match &upload.display_name {
+            Some(display_name) => serde_json::json!({ "file": { "display_name": display_name } }),
+            None => serde_json::json!({ "file": {} }),
+        };
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            let request = self
+                .client
+                .post(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                .header("X-Goog-Upload-Protocol", "resumable")
+                .header("X-Goog-Upload-Command", "start")
+                .header("X-Goog-Upload-Header-Content-Type", &upload.mime_type)
+                .json(&payload);
+
+            match upload.size {
+                Some(size) => request.header("X-Goog-Upload-Header-Content-Length", size),
+                None => request,
+            }
+        })
+        .await?;
+
+        let response = error_for_status(response).await?;
+
+        let upload_url = response
+            .headers()
+            .get("X-Goog-Upload-URL")
+            .and_then(|url| url.to_str().ok())
+            .ok_or_else(|| gemini_error("Missing upload URL"))?
+            .parse::<reqwest::Url>()
+            .map_err(|e| gemini_error(format!("Invalid upload URL: {e}")))?;
+
+        // A chunk is only known to be the last once the next one is read empty
+        let mut offset = 0;
+        let mut chunk = read_chunk(&mut reader).await?;
+
+        loop {
+            let next = read_chunk(&mut reader).await?;
+            let command = if next.is_empty() {
+                "upload, finalize"
+            } else {
+                "upload"
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!("Uploading {} bytes at offset {}", chunk.len(), offset);
+
+            let len = chunk.len() as u64;
+            let response = send_authenticated(&*self.credentials, |api_key| {
+                self.client
+                    .post(upload_url.clone())
+                    .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                    .header("X-Goog-Upload-Command", command)
+                    .header("X-Goog-Upload-Offset", offset)
+                    .body(chunk.clone())
+            })
+            .await?;
+
+            if next.is_empty() {
+                let uploaded: UploadedFile = parse_json(response).await?;
+                return Ok(uploaded.file);
+            }
+
+            error_for_status(response).await?;
+
+            offset += len;
+            chunk = next;
+        }
+    }
+
+    /// Returns the metadata of an uploaded file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if `name` does not start with `files/`, the HTTP request fails,
+    /// the API returns a non-success status code, such as for an unknown or expired file, or
+    /// the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn get_file(&self, name: &str) -> Result<GeminiFile> {
+        let url = file_url(&self.base_url, name)?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .get(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Lists the uploaded files of the project. All pages of the listing are fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn list_files(&self) -> Result<Vec<GeminiFile>> {
+        let url = join_url(&self.base_url, "v1beta/files")?;
+
+        let mut files = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("pageSize", "100")];
+            if let Some(page_token) = &page_token {
+                query.push(("pageToken", page_token.as_str()));
+            }
+
+            let response = send_authenticated(&*self.credentials, |api_key| {
+                self.client
+                    .get(url.clone())
+                    .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                    .query(&query)
+            })
+            .await?;
+
+            let page: FilesList = parse_json(response).await?;
+            files.extend(page.files);
+
+            match page.next_page_token {
+                Some(next_page_token) if !next_page_token.is_empty() => {
+                    page_token = Some(next_page_token);
+                }
+                _ => return Ok(files),
+            }
+        }
+    }
+
+    /// Deletes an uploaded file before it expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if `name` does not start with `files/`, the HTTP request fails or
+    /// the API returns a non-success status code, such as for an unknown file.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_file(&self, name: &str) -> Result<()> {
+        let url = file_url(&self.base_url, name)?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .delete(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+        })
+        .await?;
+
+        error_for_status(response).await?;
+
+        Ok(())
+    }
+
+    /// Polls an uploaded file until it is no longer processing, and returns it once `ACTIVE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if retrieving the file fails, its processing fails, it is in an
+    /// unspecified or unknown state, or the timeout of `poll` elapses first.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn wait_for_file(&self, name: &str, poll: PollOptions) -> Result<GeminiFile> {
+        let deadline = poll.timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let file = self.get_file(name).await?;
+
+            match file.state {
+                FileState::Active => return Ok(file),
+                FileState::Processing => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("File {} is {:?}", name, file.state);
+                }
+                FileState::Failed => {
+                    return Err(gemini_error(file.error.map_or_else(
+                        || format!("Processing of file {name} failed"),
+                        |error| error.message,
+                    )));
+                }
+                FileState::StateUnspecified | FileState::Unknown => {
+                    return Err(gemini_error(format!(
+                        "File {name} is in an unexpected state: {:?}",
+                        file.state
+                    )));
+                }
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() + poll.interval > deadline) {
+                return Err(gemini_error(format!("Timed out waiting for file {name}")));
+            }
+
+            tokio::time::sleep(poll.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_url() {
+        let base_url = reqwest::Url::parse("https://example.com").unwrap();
+
+        assert_eq!(
+            file_url(&base_url, "files/report").unwrap().as_str(),
+            "https://example.com/v1beta/files/report"
+        );
+        assert_eq!(
+            file_url(&base_url, "files/report#frag").unwrap().as_str(),
+            "https://example.com/v1beta/files/report%23frag"
+        );
+
+        for name in ["cachedContents/handbook", "report", "files/", "files/."] {
+            assert!(matches!(
+                file_url(&base_url, name),
+                Err(Error::InvalidUrlError(_))
+            ));
+        }
+    }
+}
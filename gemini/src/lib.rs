@@ -7,35 +7,116 @@
 
 use std::{future::ready, sync::Arc};
 
-use eventsource_stream::Eventsource;
 use futures::{FutureExt, StreamExt, stream::BoxStream};
-use latchlm_core::{AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, Error, Result};
+use latchlm_core::{
+    AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, CredentialProvider, Error, ModelPricing,
+    PricingTable, Result, StaticCredential, join_url, parse_event_stream, parse_json,
+    send_authenticated,
+};
 use latchlm_macros::AiModel;
 
 use secrecy::{ExposeSecret, SecretString};
 
+mod cache;
+pub use cache::*;
+
+mod files;
+pub use files::*;
+
+mod request;
+use request::GeminiRequest;
+pub use request::{
+    GeminiRequestOptions, GeminiTool, HarmBlockThreshold, HarmCategory, SafetySetting,
+};
+
 mod response;
 pub use response::*;
 
+#[cfg(feature = "vertex")]
+mod vertex;
+#[cfg(feature = "vertex")]
+pub use vertex::*;
+
 /// Variants representing supported Gemini models.
 ///
 /// These variants map to the actual model identifiers used by the Gemini API.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AiModel)]
+/// Models without a variant, such as those returned by [`Gemini::models`],
+/// can be used through [`GeminiModel::Custom`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, AiModel)]
 #[non_exhaustive]
 pub enum GeminiModel {
-    #[model(id = "gemini-2.0-flash", name = "Gemini 2.0 Flash")]
+    #[model(
+        id = "gemini-2.0-flash",
+        name = "Gemini 2.0 Flash",
+        alias = "gemini-2.0-flash-001",
+        context_window = 1_048_576,
+        max_output_tokens = 8_192,
+        input_modalities = "text, image, audio, video, document",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Flash20,
-    #[model(id = "gemini-2.0-flash-lite", name = "Gemini 2.0 Flash Lite")]
+    #[model(
+        id = "gemini-2.0-flash-lite",
+        name = "Gemini 2.0 Flash Lite",
+        alias = "gemini-2.0-flash-lite-001",
+        context_window = 1_048_576,
+        max_output_tokens = 8_192,
+        input_modalities = "text, image, audio, video, document",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Flash20Lite,
-    #[model(id = "gemini-2.5-flash", name = "Gemini 2.5 Flash")]
+    #[model(
+        id = "gemini-2.5-flash",
+        name = "Gemini 2.5 Flash",
+        default,
+        context_window = 1_048_576,
+        max_output_tokens = 65_536,
+        input_modalities = "text, image, audio, video, document",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Flash25,
-    #[model(id = "gemini-2.5-pro", name = "Gemini 2.5 Pro")]
+    #[model(
+        id = "gemini-2.5-pro",
+        name = "Gemini 2.5 Pro",
+        context_window = 1_048_576,
+        max_output_tokens = 65_536,
+        input_modalities = "text, image, audio, video, document",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Pro25,
+    #[deprecated(note = "Use gemini-2.5-flash instead")]
     #[model(
         id = "gemini-2.0-flash-thinking-exp-01-21",
-        name = "Gemini 2.0 Flash Thinking"
+        name = "Gemini 2.0 Flash Thinking",
+        context_window = 1_048_576,
+        max_output_tokens = 65_536,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = false,
+        structured_output = false,
+        reasoning = true,
+        streaming = true
     )]
     FlashThinking,
+    /// Any other model, identified by its id
+    #[model(other)]
+    Custom(String),
 }
 
 impl std::fmt::Display for GeminiModel {
@@ -44,6 +125,37 @@ impl std::fmt::Display for GeminiModel {
     }
 }
 
+impl GeminiModel {
+    /// Returns the list price of the model, in US dollars per million tokens.
+    ///
+    /// Prices are those of the paid tier for prompts of up to 200k tokens.
+    /// Experimental models are free of charge.
+    #[must_use]
+    #[allow(deprecated)]
+    pub fn pricing(&self) -> Option<ModelPricing> {
+        let pricing = match self {
+            Self::Flash20 => ModelPricing::new(0.10, 0.40).with_cached_input(0.025),
+            Self::Flash20Lite => ModelPricing::new(0.075, 0.30),
+            Self::Flash25 => ModelPricing::new(0.30, 2.50).with_cached_input(0.03),
+            Self::Pro25 => ModelPricing::new(1.25, 10.00).with_cached_input(0.125),
+            Self::FlashThinking => ModelPricing::new(0.0, 0.0),
+            Self::Custom(_) => return None,
+        };
+
+        Some(pricing)
+    }
+
+    /// Returns a [`PricingTable`] with the list prices of all Gemini models.
+    #[must_use]
+    pub fn pricing_table() -> PricingTable {
+        Self::variants()
+            .iter()
+            .filter_map(|variant| Self::try_from(variant.id.as_ref()).ok())
+            .filter_map(|model| Some((model.as_ref().to_owned(), model.pricing()?)))
+            .collect()
+    }
+}
+
 /// Errors that can occur when building a [`Gemini`] client.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GeminiError {
@@ -51,6 +163,10 @@ pub enum GeminiError {
     MissingApiKeyError,
     /// Returned when no HTTP client is provided
     MissingClientError,
+    /// Returned when no Google Cloud project is provided to a `Vertex` client
+    MissingProjectError,
+    /// Returned when no credentials are provided to a `Vertex` client
+    MissingCredentialsError,
 }
 
 impl From<GeminiError> for Error {
@@ -64,6 +180,14 @@ impl From<GeminiError> for Error {
                 provider: "Gemini".into(),
                 error: "Missing reqwest::Client".into(),
             },
+            GeminiError::MissingProjectError => Self::ProviderError {
+                provider: "Vertex".into(),
+                error: "Missing Google Cloud project".into(),
+            },
+            GeminiError::MissingCredentialsError => Self::ProviderError {
+                provider: "Vertex".into(),
+                error: "Missing credentials".into(),
+            },
         }
     }
 }
@@ -75,6 +199,8 @@ impl std::fmt::Display for GeminiError {
         match self {
             Self::MissingApiKeyError => write!(f, "API key is required"),
             Self::MissingClientError => write!(f, "HTTP client is required"),
+            Self::MissingProjectError => write!(f, "Google Cloud project is required"),
+            Self::MissingCredentialsError => write!(f, "Credentials are required"),
         }
     }
 }
@@ -83,7 +209,8 @@ impl std::fmt::Display for GeminiError {
 #[derive(Default)]
 pub struct GeminiBuilder {
     client: Option<reqwest::Client>,
-    api_key: Option<SecretString>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    base_url: Option<reqwest::Url>,
 }
 
 impl GeminiBuilder {
@@ -102,8 +229,26 @@ impl GeminiBuilder {
 
     /// Sets the API key
     #[must_use]
-    pub fn api_key(mut self, api_key: SecretString) -> Self {
-        self.api_key = Some(api_key);
+    pub fn api_key(self, api_key: SecretString) -> Self {
+        self.credentials(StaticCredential::new(api_key))
+    }
+
+    /// Sets the provider consulted for the API key before every request
+    ///
+    /// Use this instead of [`GeminiBuilder::api_key`] to rotate keys without rebuilding the client.
+    #[must_use]
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    /// Sets the base URL the API paths are appended to
+    ///
+    /// Defaults to `https://generativelanguage.googleapis.com`. Any path prefix is kept, so
+    /// requests can be routed through proxies, gateways and regional endpoints.
+    #[must_use]
+    pub fn base_url(mut self, base_url: reqwest::Url) -> Self {
+        self.base_url = Some(base_url);
         self
     }
 
@@ -111,22 +256,32 @@ impl GeminiBuilder {
     pub fn api_key_from_env(mut self) -> std::result::Result<Self, std::env::VarError> {
         let api_key = std::env::var("GEMINI_API_KEY")?;
 
-        self.api_key = Some(SecretString::from(api_key));
+        self.credentials = Some(Arc::new(StaticCredential::new(SecretString::from(api_key))));
         Ok(self)
     }
 
     /// Constructs a [`Gemini`] instance
     ///
     /// # Errors
-    /// Returns an error if the client or API key are missing
-    ///
-    /// # Panics
-    /// Panics if the base URL is not set, which should never happen since it has a default.
+    /// Returns an error if the client or API key are missing, or the base URL cannot be a base
     pub fn build(self) -> Result<Gemini> {
         let client = self.client.ok_or(GeminiError::MissingClientError)?;
-        let api_key = self.api_key.ok_or(GeminiError::MissingApiKeyError)?;
+        let credentials = self.credentials.ok_or(GeminiError::MissingApiKeyError)?;
+
+        let base_url = match self.base_url {
+            Some(base_url) if base_url.cannot_be_a_base() => {
+                return Err(Error::InvalidUrlError(base_url.to_string()));
+            }
+            Some(base_url) => base_url,
+            None => reqwest::Url::parse(Gemini::BASE_URL)
+                .map_err(|_| Error::InvalidUrlError(Gemini::BASE_URL.to_owned()))?,
+        };
 
-        Ok(Gemini::new(client, api_key))
+        Ok(Gemini {
+            client,
+            base_url,
+            credentials,
+        })
     }
 }
 
@@ -135,7 +290,7 @@ impl GeminiBuilder {
 pub struct Gemini {
     client: reqwest::Client,
     base_url: reqwest::Url,
-    api_key: Arc<SecretString>,
+    credentials: Arc<dyn CredentialProvider>,
 }
 
 impl Gemini {
@@ -156,24 +311,20 @@ impl Gemini {
         Self {
             client,
             base_url: reqwest::Url::parse(Self::BASE_URL).expect("Failed to parse base url"),
-            api_key: Arc::new(api_key),
+            credentials: Arc::new(StaticCredential::new(api_key)),
         }
     }
 
     /// Creates a new `Gemini` client instance with a custom base URL.
     ///
-    /// This constructor is intended exclusively for testing and mocking scenarios
-    /// and should **never** be used in production code.
+    /// The API paths, such as `v1beta/models`, are appended to the base URL, keeping any
+    /// path prefix it has.
     ///
     /// # Arguments
     ///
     /// * `client` - A reference to a preconfigured [`reqwest::Client`].
     /// * `base_url` - The base URL for the Gemini API.
     /// * `api_key` - The API key wrapped in `SecretString` for secure handling
-    ///
-    /// # Feature
-    /// Requires the `test-utils` feature flag.
-    #[cfg(feature = "test-utils")]
     #[must_use]
     pub fn new_with_base_url(
         client: reqwest::Client,
@@ -183,7 +334,7 @@ impl Gemini {
         Self {
             client,
             base_url,
-            api_key: Arc::new(api_key),
+            credentials: Arc::new(StaticCredential::new(api_key)),
         }
     }
 
@@ -241,6 +392,7 @@ impl Gemini {
     ///         GeminiModel::Flash25,
     ///         AiRequest {
     ///             text: "Hello".into(),
+    ///             ..Default::default()
     ///         }
     ///     ).await?;
     ///
@@ -249,54 +401,94 @@ impl Gemini {
     /// }
     /// ```
     /// [`Error`]: latchlm_core::Error
-    #[allow(clippy::expect_used)]
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn request(&self, model: GeminiModel, request: AiRequest) -> Result<GeminiResponse> {
-        let url = self
-            .base_url
-            .join(&format!(
-                "/v1beta/models/{}:generateContent",
-                model.as_ref()
-            ))
-            .expect("Failed to parse the URL");
-
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            Self::X_GOOG_API_KEY,
-            self.api_key
-                .expose_secret()
-                .parse()
-                .expect("Failed to parse header"),
-        );
-
-        let payload = serde_json::json!({"contents": [{"parts": {"text": request.text}}]});
-
-        let response = self
-            .client
-            .post(url)
-            .headers(headers)
-            .json(&payload)
-            .send()
-            .await?;
+        self.request_with_options(model, request, GeminiRequestOptions::default())
+            .await
+    }
 
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await?;
+    /// Sends a request to the Gemini API with Gemini-specific options.
+    ///
+    /// Enabling [`GeminiTool::google_search`] grounds the response with search results,
+    /// reported in [`GeminiResponse::grounding_metadata`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Gemini::request`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn request_with_options(
+        &self,
+        model: GeminiModel,
+        request: AiRequest,
+        options: GeminiRequestOptions,
+    ) -> Result<GeminiResponse> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
+        }
 
-            #[cfg(feature = "tracing")]
-            tracing::error!("API error: {}", &message);
+        let url = join_url(
+            &self.base_url,
+            &format!("v1beta/models/{}:generateContent", model.as_ref()),
+        )?;
 
-            return Err(Error::ApiError { status, message });
-        }
+        let payload = GeminiRequest::from(request).with_options(options);
 
-        let bytes = response.bytes().await?;
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                .json(&payload)
+        })
+        .await?;
 
-        #[cfg(feature = "tracing")]
-        tracing::debug!("Received bytes: {:?}", bytes);
+        parse_json(response).await
+    }
+
+    /// Lists the models available through the Gemini API.
+    ///
+    /// All pages of the listing are fetched. Use [`GeminiModelInfo::model`] to
+    /// make requests with any of the returned models.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if:
+    /// - The HTTP request fails (network issues, timeout, etc.)
+    /// - The API returns a non-success status code
+    /// - The response body cannot be parsed as valid JSON
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn models(&self) -> Result<Vec<GeminiModelInfo>> {
+        let url = join_url(&self.base_url, "v1beta/models")?;
 
-        let response: GeminiResponse = serde_json::from_slice(&bytes)?;
+        let mut models = Vec::new();
+        let mut page_token: Option<String> = None;
 
-        Ok(response)
+        loop {
+            let mut query = vec![("pageSize", "1000")];
+            if let Some(page_token) = &page_token {
+                query.push(("pageToken", page_token.as_str()));
+            }
+
+            let response = send_authenticated(&*self.credentials, |api_key| {
+                self.client
+                    .get(url.clone())
+                    .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                    .query(&query)
+            })
+            .await?;
+
+            let page: GeminiModelsList = parse_json(response).await?;
+            models.extend(page.models);
+
+            match page.next_page_token {
+                Some(next_page_token) if !next_page_token.is_empty() => {
+                    page_token = Some(next_page_token);
+                }
+                _ => return Ok(models),
+            }
+        }
     }
 
     /// Sends a streaming request to the Gemini API and prints chunks as they arrive.
@@ -305,68 +497,55 @@ impl Gemini {
     ///
     /// * `model` - The model to use for the request.
     /// * `request` - The request to send.
-    #[allow(clippy::expect_used)]
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn streaming_request(
         &self,
         model: GeminiModel,
         request: AiRequest,
     ) -> Result<BoxStream<'_, Result<GeminiResponse>>> {
-        let url = self
-            .base_url
-            .join(&format!(
-                "/v1beta/models/{}:streamGenerateContent?alt=sse",
+        self.streaming_request_with_options(model, request, GeminiRequestOptions::default())
+            .await
+    }
+
+    /// Sends a streaming request to the Gemini API with Gemini-specific options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails or the API returns a non-success
+    /// status code.
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn streaming_request_with_options(
+        &self,
+        model: GeminiModel,
+        request: AiRequest,
+        options: GeminiRequestOptions,
+    ) -> Result<BoxStream<'_, Result<GeminiResponse>>> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
+        }
+
+        let url = join_url(
+            &self.base_url,
+            &format!(
+                "v1beta/models/{}:streamGenerateContent?alt=sse",
                 model.as_ref()
-            ))
-            .expect("Failed to parse URL");
-
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            Self::X_GOOG_API_KEY,
-            self.api_key
-                .expose_secret()
-                .parse()
-                .expect("Failed to parse header"),
-        );
-
-        let payload = serde_json::json!({"contents": [{"parts": {"text": request.text}}]});
-
-        let response = self
-            .client
-            .post(url)
-            .headers(headers)
-            .json(&payload)
-            .send()
-            .await?;
+            ),
+        )?;
 
-        let stream = response
-            .bytes_stream()
-            .eventsource()
-            .filter_map(|event| async {
-                let event = match event {
-                    Ok(event) => {
-                        #[cfg(feature = "tracing")]
-                        tracing::debug!("Received event: {:?}", event);
-
-                        event
-                    }
-                    Err(err) => {
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("Error receiving event: {:?}", err);
-
-                        return Some(Err(Error::ProviderError {
-                            provider: "Gemini".to_string(),
-                            error: err.to_string(),
-                        }));
-                    }
-                };
-
-                let data = event.data;
-
-                Some(serde_json::from_str::<GeminiResponse>(&data).map_err(Into::into))
-            });
-
-        Ok(Box::pin(stream))
+        let payload = GeminiRequest::from(request).with_options(options);
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .header(Self::X_GOOG_API_KEY, api_key.expose_secret())
+                .json(&payload)
+        })
+        .await?;
+
+        parse_event_stream(response, "Gemini").await
     }
 }
 
@@ -422,10 +601,12 @@ impl AiProvider for Gemini {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use latchlm_core::{Modality, ModelCapabilities};
     use proptest::prelude::*;
 
     proptest! {
         #[test]
+        #[allow(deprecated)]
         fn test_gemini_model_try_from_valid(model in prop_oneof![
             Just(GeminiModel::Flash20),
             Just(GeminiModel::Flash20Lite),
@@ -439,16 +620,63 @@ mod tests {
         }
 
         #[test]
-        fn test_gemini_model_try_from_invalid(model_str in "\\PC*") {
-            let valid_ids: Vec<_> = GeminiModel::variants()
-                .iter()
-                .map(|v| v.id.clone())
-                .collect();
-
-            prop_assume!(!valid_ids.contains(&model_str.clone().into()));
+        fn test_gemini_model_try_from_custom(model_str in "\\PC*") {
+            prop_assume!(!model_str.trim().is_empty());
+            // Excludes the ids and aliases of the known variants
+            prop_assume!(!matches!(
+                GeminiModel::try_from(model_str.as_str()),
+                Ok(model) if !matches!(model, GeminiModel::Custom(_))
+            ));
+
+            let model = GeminiModel::try_from(model_str.as_str()).unwrap();
+            prop_assert_eq!(model.as_ref(), model_str.as_str());
+            prop_assert_eq!(model, GeminiModel::Custom(model_str));
+        }
 
+        #[test]
+        fn test_gemini_model_try_from_blank(model_str in "\\s*") {
             let err = GeminiModel::try_from(model_str.as_str()).unwrap_err();
             prop_assert_eq!(err.to_string(), format!("Invalid model name: {}", model_str));
         }
     }
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = GeminiModel::Pro25.capabilities();
+        assert_eq!(capabilities.context_window, Some(1_048_576));
+        assert!(capabilities.accepts(Modality::Image));
+        assert_eq!(capabilities.tool_calling, Some(true));
+
+        for variant in GeminiModel::variants() {
+            assert!(variant.capabilities.context_window.is_some());
+        }
+
+        let custom = GeminiModel::Custom("custom-model".to_owned());
+        assert_eq!(custom.capabilities(), ModelCapabilities::new());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_aliases_and_defaults() {
+        let model = GeminiModel::try_from("gemini-2.0-flash-001").unwrap();
+        assert_eq!(model, GeminiModel::Flash20);
+        assert_eq!(model.as_ref(), "gemini-2.0-flash");
+
+        assert_eq!(GeminiModel::default(), GeminiModel::Flash25);
+        assert!(GeminiModel::FlashThinking.model_id().deprecated.is_some());
+        assert!(GeminiModel::Flash25.model_id().deprecated.is_none());
+    }
+
+    #[test]
+    fn test_pricing_table_covers_all_models() {
+        let table = GeminiModel::pricing_table();
+
+        for variant in GeminiModel::variants() {
+            assert!(
+                table.get(&variant.id).is_some(),
+                "missing price for {}",
+                variant.id
+            );
+        }
+    }
 }
@@ -0,0 +1,342 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! This module contains the structs used to serialize
+//! the Gemini API requests
+
+use latchlm_core::{AiRequest, ReasoningEffort, ReasoningOptions};
+use serde::Serialize;
+
+use crate::{Content, FileData, Text};
+
+/// A tool hosted by Google that the model may use to ground its response.
+///
+/// The sources the model used are reported in [`GeminiResponse::grounding_metadata`] and
+/// [`GeminiResponse::url_context_metadata`].
+///
+/// [`GeminiResponse::grounding_metadata`]: crate::GeminiResponse::grounding_metadata
+/// [`GeminiResponse::url_context_metadata`]: crate::GeminiResponse::url_context_metadata
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum GeminiTool {
+    /// Grounds the response with Google Search results
+    GoogleSearch {},
+    /// Retrieves the content of the URLs given in the prompt
+    UrlContext {},
+}
+
+impl GeminiTool {
+    /// Returns the Google Search tool.
+    #[must_use]
+    pub const fn google_search() -> Self {
+        Self::GoogleSearch {}
+    }
+
+    /// Returns the URL context tool.
+    #[must_use]
+    pub const fn url_context() -> Self {
+        Self::UrlContext {}
+    }
+}
+
+/// A category of harmful content filtered by the safety settings.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HarmCategory {
+    #[serde(rename = "HARM_CATEGORY_HARASSMENT")]
+    Harassment,
+    #[serde(rename = "HARM_CATEGORY_HATE_SPEECH")]
+    HateSpeech,
+    #[serde(rename = "HARM_CATEGORY_SEXUALLY_EXPLICIT")]
+    SexuallyExplicit,
+    #[serde(rename = "HARM_CATEGORY_DANGEROUS_CONTENT")]
+    DangerousContent,
+    #[serde(rename = "HARM_CATEGORY_CIVIC_INTEGRITY")]
+    CivicIntegrity,
+}
+
+/// The probability of harm from which content is blocked.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum HarmBlockThreshold {
+    /// Block content with a low, medium or high probability of harm
+    BlockLowAndAbove,
+    /// Block content with a medium or high probability of harm
+    BlockMediumAndAbove,
+    /// Block content with a high probability of harm
+    BlockOnlyHigh,
+    /// Never block content, but still report its safety ratings
+    BlockNone,
+    /// Turn the safety filter off
+    Off,
+}
+
+/// The blocking threshold of a single harm category.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SafetySetting {
+    pub category: HarmCategory,
+    pub threshold: HarmBlockThreshold,
+}
+
+impl SafetySetting {
+    /// Creates a setting blocking `category` from `threshold`.
+    #[must_use]
+    pub const fn new(category: HarmCategory, threshold: HarmBlockThreshold) -> Self {
+        Self {
+            category,
+            threshold,
+        }
+    }
+}
+
+/// Options of a Gemini request that have no provider-agnostic equivalent.
+///
+/// Pass them to [`Gemini::request_with_options`] or, with the `vertex` feature,
+/// `Vertex::request_with_options`.
+///
+/// [`Gemini::request_with_options`]: crate::Gemini::request_with_options
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiRequestOptions {
+    /// The hosted tools the model may use
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<GeminiTool>,
+    /// The blocking thresholds of the safety filters, replacing the default of the model
+    /// for the listed categories
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+    /// The name of a cached content to prepend to the request, e.g. `cachedContents/abc123`
+    ///
+    /// The request must use the model the content was cached for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_content: Option<String>,
+    /// Uploaded files attached to the prompt, before its text
+    #[serde(skip)]
+    pub files: Vec<FileData>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThinkingConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_budget: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_thoughts: Option<bool>,
+}
+
+impl From<ReasoningOptions> for ThinkingConfig {
+    fn from(value: ReasoningOptions) -> Self {
+        // Gemini only accepts a token budget, so efforts are mapped to the
+        // budgets used by the Gemini OpenAI compatibility layer.
+        let thinking_budget = value
+            .budget_tokens
+            .or(value.effort.map(|effort| match effort {
+                ReasoningEffort::Minimal | ReasoningEffort::Low => 1024,
+                ReasoningEffort::Medium => 8192,
+                ReasoningEffort::High => 24576,
+            }));
+
+        Self {
+            thinking_budget,
+            include_thoughts: value.include_reasoning.then_some(true),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    candidate_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<ThinkingConfig>,
+}
+
+/// The body of a `generateContent` or `streamGenerateContent` request.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GeminiRequest {
+    contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GenerationConfig>,
+    #[serde(flatten)]
+    options: GeminiRequestOptions,
+}
+
+impl GeminiRequest {
+    pub(crate) fn with_options(mut self, mut options: GeminiRequestOptions) -> Self {
+        if let Some(content) = self.contents.first_mut() {
+            content.parts.splice(
+                0..0,
+                options.files.drain(..).map(|file_data| Text {
+                    file_data: Some(file_data),
+                    ..Default::default()
+                }),
+            );
+        }
+
+        self.options = options;
+        self
+    }
+}
+
+impl From<AiRequest> for GeminiRequest {
+    fn from(value: AiRequest) -> Self {
+        let generation_config = GenerationConfig {
+            candidate_count: value.candidate_count,
+            thinking_config: value.reasoning.map(Into::into),
+        };
+
+        Self {
+            contents: vec![Content {
+                parts: vec![Text {
+                    text: value.text,
+                    ..Default::default()
+                }],
+                role: Some("user".to_owned()),
+            }],
+            generation_config: (generation_config != GenerationConfig::default())
+                .then_some(generation_config),
+            options: GeminiRequestOptions::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_text_only() {
+        let request = GeminiRequest::from(AiRequest {
+            text: "Hello".into(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({"contents": [{"parts": [{"text": "Hello"}], "role": "user"}]})
+        );
+    }
+
+    #[test]
+    fn test_serialize_thinking_config() {
+        let request = GeminiRequest::from(AiRequest {
+            text: "Hello".into(),
+            reasoning: Some(ReasoningOptions {
+                effort: Some(ReasoningEffort::High),
+                include_reasoning: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap()["generationConfig"],
+            serde_json::json!({"thinkingConfig": {"thinkingBudget": 24576, "includeThoughts": true}})
+        );
+    }
+
+    #[test]
+    fn test_serialize_candidate_count() {
+        let request = GeminiRequest::from(AiRequest {
+            text: "Hello".into(),
+            candidate_count: Some(2),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap()["generationConfig"],
+            serde_json::json!({"candidateCount": 2})
+        );
+    }
+
+    #[test]
+    fn test_budget_takes_precedence_over_effort() {
+        let config = ThinkingConfig::from(ReasoningOptions {
+            effort: Some(ReasoningEffort::Low),
+            budget_tokens: Some(0),
+            include_reasoning: false,
+        });
+
+        assert_eq!(config.thinking_budget, Some(0));
+        assert_eq!(config.include_thoughts, None);
+    }
+
+    #[test]
+    fn test_serialize_tools() {
+        let request = GeminiRequest::from(AiRequest {
+            text: "Hello".into(),
+            ..Default::default()
+        })
+        .with_options(GeminiRequestOptions {
+            tools: vec![GeminiTool::google_search(), GeminiTool::url_context()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap()["tools"],
+            serde_json::json!([{"googleSearch": {}}, {"urlContext": {}}])
+        );
+    }
+
+    #[test]
+    fn test_serialize_safety_settings() {
+        let request = GeminiRequest::from(AiRequest {
+            text: "Hello".into(),
+            ..Default::default()
+        })
+        .with_options(GeminiRequestOptions {
+            safety_settings: vec![
+                SafetySetting::new(HarmCategory::Harassment, HarmBlockThreshold::BlockOnlyHigh),
+                SafetySetting::new(HarmCategory::DangerousContent, HarmBlockThreshold::Off),
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap()["safetySettings"],
+            serde_json::json!([
+                {"category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_ONLY_HIGH"},
+                {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "OFF"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_serialize_files() {
+        let request = GeminiRequest::from(AiRequest {
+            text: "Summarize".into(),
+            ..Default::default()
+        })
+        .with_options(GeminiRequestOptions {
+            files: vec![FileData {
+                mime_type: "application/pdf".into(),
+                file_uri: "https://example.com/files/abc".into(),
+            }],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({
+                "contents": [{
+                    "parts": [
+                        {
+                            "fileData": {
+                                "mimeType": "application/pdf",
+                                "fileUri": "https://example.com/files/abc"
+                            }
+                        },
+                        {"text": "Summarize"}
+                    ],
+                    "role": "user"
+                }]
+            })
+        );
+    }
+}
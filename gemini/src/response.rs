@@ -5,94 +5,552 @@
 //! This module contains the structs used to deserialize
 //! the Gemini API responses
 
-use latchlm_core::{AiResponse, TokenUsage};
+use std::collections::BTreeMap;
+
+use latchlm_core::{
+    AiResponse, Citation, Error, FinishReason, Modality, Result, SafetyInfo, TokenUsage,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::GeminiModel;
+
+/// The number of tokens of a single modality.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
-struct PromptTokensDetails {
+struct ModalityTokenCount {
     modality: String,
+    #[serde(default)]
     token_count: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
-#[serde(rename_all = "camelCase")]
-struct CandidatesTokensDetails {
-    modality: String,
-    token_count: u64,
+fn by_modality(details: &[ModalityTokenCount]) -> BTreeMap<Modality, u64> {
+    let mut counts = BTreeMap::new();
+
+    for detail in details {
+        let modality = match detail.modality.as_str() {
+            "TEXT" => Modality::Text,
+            "IMAGE" => Modality::Image,
+            "AUDIO" => Modality::Audio,
+            "VIDEO" => Modality::Video,
+            "DOCUMENT" => Modality::Document,
+            _ => Modality::Unspecified,
+        };
+
+        let count = counts.entry(modality).or_insert(0_u64);
+        *count = count.saturating_add(detail.token_count);
+    }
+
+    counts
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct UsageMetadata {
+    // Zero counts are omitted, such as the candidate tokens of a blocked prompt
+    #[serde(default)]
     prompt_token_count: u64,
+    #[serde(default)]
     candidates_token_count: u64,
+    #[serde(default)]
     total_token_count: u64,
-    prompt_tokens_details: Vec<PromptTokensDetails>,
+    #[serde(default)]
+    prompt_tokens_details: Vec<ModalityTokenCount>,
     thoughts_token_count: Option<u64>,
-    candidates_tokens_details: Option<Vec<CandidatesTokensDetails>>,
+    cached_content_token_count: Option<u64>,
+    candidates_tokens_details: Option<Vec<ModalityTokenCount>>,
+}
+
+impl From<&UsageMetadata> for TokenUsage {
+    fn from(value: &UsageMetadata) -> Self {
+        Self {
+            input_tokens: Some(value.prompt_token_count),
+            cached_input_tokens: value.cached_content_token_count,
+            // Gemini reports thoughts separately from the candidates
+            output_tokens: Some(
+                value
+                    .candidates_token_count
+                    .saturating_add(value.thoughts_token_count.unwrap_or_default()),
+            ),
+            reasoning_tokens: value.thoughts_token_count,
+            total_tokens: Some(value.total_token_count),
+            input_tokens_by_modality: by_modality(&value.prompt_tokens_details),
+            output_tokens_by_modality: by_modality(
+                value
+                    .candidates_tokens_details
+                    .as_deref()
+                    .unwrap_or_default(),
+            ),
+        }
+    }
 }
 
+/// A part of a content, holding either text or a reference to an uploaded file.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct Text {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub text: String,
+    /// Whether this part is a summary of the model's thoughts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thought: Option<bool>,
+    /// A file uploaded with the Files API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_data: Option<FileData>,
+}
+
+/// A reference to a file uploaded with the Files API, used as a part of a prompt.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FileData {
+    pub mime_type: String,
+    /// The URI of the file, as returned by the Files API
+    pub file_uri: String,
+}
+
+impl Text {
+    fn is_thought(&self) -> bool {
+        self.thought.unwrap_or(false)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Content {
     pub parts: Vec<Text>,
+    /// The producer of the content, either `user` or `model`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// The rating of some content for a single harm category.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SafetyRating {
+    pub category: String,
+    pub probability: String,
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+impl From<&SafetyRating> for latchlm_core::SafetyRating {
+    fn from(value: &SafetyRating) -> Self {
+        Self {
+            category: value.category.clone(),
+            probability: value.probability.clone(),
+            blocked: value.blocked,
+        }
+    }
+}
+
+/// Feedback on the prompt, reported when the prompt is blocked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFeedback {
+    pub block_reason: Option<String>,
+    #[serde(default)]
+    pub safety_ratings: Vec<SafetyRating>,
+}
+
+/// The sources used to ground a candidate, reported when a grounding tool is enabled.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GroundingMetadata {
+    /// The queries the model sent to Google Search
+    #[serde(default)]
+    pub web_search_queries: Vec<String>,
+    /// The Google Search suggestions to display with the grounded response
+    pub search_entry_point: Option<SearchEntryPoint>,
+    /// The sources the model retrieved
+    #[serde(default)]
+    pub grounding_chunks: Vec<GroundingChunk>,
+    /// The spans of the response supported by the sources
+    #[serde(default)]
+    pub grounding_supports: Vec<GroundingSupport>,
 }
 
+/// The Google Search suggestions that must be displayed with a response grounded by search.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
+pub struct SearchEntryPoint {
+    /// The HTML and CSS of the suggestions, to embed in a web page
+    pub rendered_content: Option<String>,
+    /// The suggestions as base64-encoded JSON
+    pub sdk_blob: Option<String>,
+}
+
+/// A source retrieved to ground a response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GroundingChunk {
+    /// A web page found by Google Search or retrieved from a URL of the prompt
+    pub web: Option<WebSource>,
+}
+
+/// A web page used as a grounding source.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSource {
+    /// The URL of the page, a redirect through Google for search results
+    pub uri: String,
+    pub title: Option<String>,
+}
+
+/// A span of the response and the sources supporting it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GroundingSupport {
+    pub segment: Segment,
+    /// The indices of the supporting sources in [`GroundingMetadata::grounding_chunks`]
+    #[serde(default)]
+    pub grounding_chunk_indices: Vec<usize>,
+    /// The confidence of each supporting source, between 0 and 1
+    #[serde(default)]
+    pub confidence_scores: Vec<f64>,
+}
+
+/// A span of the text of a response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    /// The index of the part containing the span
+    #[serde(default)]
+    pub part_index: usize,
+    /// The byte offset of the start of the span in the part
+    #[serde(default)]
+    pub start_index: usize,
+    /// The byte offset of the end of the span in the part, exclusive
+    #[serde(default)]
+    pub end_index: usize,
+    #[serde(default)]
+    pub text: String,
+}
+
+/// The URLs retrieved by the URL context tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlContextMetadata {
+    #[serde(default)]
+    pub url_metadata: Vec<UrlMetadata>,
+}
+
+/// The outcome of retrieving a single URL.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlMetadata {
+    pub retrieved_url: String,
+    /// The status of the retrieval, e.g. `URL_RETRIEVAL_STATUS_SUCCESS`
+    pub url_retrieval_status: String,
+}
+
+impl GroundingMetadata {
+    /// Returns the web sources of the metadata as provider-agnostic citations.
+    ///
+    /// Every supported span yields one citation per supporting source, followed by the
+    /// sources that support no span.
+    #[must_use]
+    pub fn citations(&self) -> Vec<Citation> {
+        let web = |index: usize| {
+            self.grounding_chunks
+                .get(index)
+                .and_then(|chunk| chunk.web.as_ref())
+        };
+
+        let mut cited = vec![false; self.grounding_chunks.len()];
+        let mut citations = Vec::new();
+
+        for support in &self.grounding_supports {
+            for &index in &support.grounding_chunk_indices {
+                let Some(source) = web(index) else {
+                    continue;
+                };
+
+                cited[index] = true;
+                citations.push(Citation {
+                    url: source.uri.clone(),
+                    title: source.title.clone(),
+                    text: Some(support.segment.text.clone()),
+                });
+            }
+        }
+
+        citations.extend(
+            cited
+                .iter()
+                .enumerate()
+                .filter(|(_, cited)| !**cited)
+                .filter_map(|(index, _)| web(index))
+                .map(|source| Citation {
+                    url: source.uri.clone(),
+                    title: source.title.clone(),
+                    text: None,
+                }),
+        );
+
+        citations
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct Candidate {
+    // Candidates blocked by the safety filters have no content
+    #[serde(default)]
     content: Content,
     finish_reason: Option<String>,
+    #[serde(default)]
+    safety_ratings: Vec<SafetyRating>,
     index: Option<u64>,
     avg_log_probs: Option<i64>,
+    grounding_metadata: Option<GroundingMetadata>,
+    url_context_metadata: Option<UrlContextMetadata>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GeminiResponse {
+    // Blocked prompts have no candidates
+    #[serde(default)]
     candidates: Vec<Candidate>,
+    prompt_feedback: Option<PromptFeedback>,
+    #[serde(default)]
     usage_metadata: UsageMetadata,
     model_version: String,
     response_id: String,
 }
 
+impl Candidate {
+    /// Returns the text of the candidate, without thought summaries.
+    fn text(&self) -> String {
+        self.content
+            .parts
+            .iter()
+            .filter(|text| !text.is_thought())
+            .map(|text| text.text.as_str())
+            .collect()
+    }
+
+    /// Returns the thought summaries of the candidate, if any.
+    fn reasoning(&self) -> Option<String> {
+        let thoughts = self
+            .content
+            .parts
+            .iter()
+            .filter(|text| text.is_thought())
+            .map(|text| text.text.as_str())
+            .collect::<Vec<_>>();
+
+        (!thoughts.is_empty()).then(|| thoughts.concat())
+    }
+
+    /// Returns the safety ratings of the candidate.
+    #[must_use]
+    pub fn safety_ratings(&self) -> &[SafetyRating] {
+        &self.safety_ratings
+    }
+
+    /// Returns the normalized reason the candidate stopped generating.
+    fn finish_reason(&self) -> Option<FinishReason> {
+        Some(match self.finish_reason.as_deref()? {
+            "STOP" => FinishReason::Stop,
+            "MAX_TOKENS" => FinishReason::Length,
+            "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII"
+            | "IMAGE_SAFETY" => FinishReason::ContentFilter,
+            other => FinishReason::Other(other.to_owned()),
+        })
+    }
+}
+
 impl From<GeminiResponse> for AiResponse {
     fn from(value: GeminiResponse) -> Self {
         Self {
             text: value.extract_text(),
-            token_usage: TokenUsage {
-                input_tokens: Some(value.usage_metadata.prompt_token_count),
-                output_tokens: Some(value.usage_metadata.candidates_token_count),
-                total_tokens: Some(value.usage_metadata.total_token_count),
-            },
+            reasoning: value.extract_reasoning(),
+            token_usage: (&value.usage_metadata).into(),
+            finish_reason: value.finish_reason(),
+            safety: value.safety(),
+            candidates: value.extract_candidates(),
+            citations: value
+                .grounding_metadata()
+                .map(GroundingMetadata::citations)
+                .unwrap_or_default(),
         }
     }
 }
 
 impl GeminiResponse {
+    /// Returns the text of the first candidate.
     #[must_use]
     pub fn extract_text(&self) -> String {
+        self.candidates
+            .first()
+            .map(Candidate::text)
+            .unwrap_or_default()
+    }
+
+    /// Returns the candidates of the response, in the order returned by the API.
+    #[must_use]
+    pub fn candidates(&self) -> &[Candidate] {
+        &self.candidates
+    }
+
+    /// Returns every candidate of the response.
+    #[must_use]
+    pub fn extract_candidates(&self) -> Vec<latchlm_core::Candidate> {
         self.candidates
             .iter()
-            .flat_map(|candidate| {
-                candidate
-                    .content
-                    .parts
-                    .iter()
-                    .map(|text| text.text.as_str())
+            .zip(0..)
+            .map(|(candidate, position)| latchlm_core::Candidate {
+                index: candidate.index.unwrap_or(position),
+                text: candidate.text(),
+                reasoning: candidate.reasoning(),
+                finish_reason: candidate.finish_reason(),
             })
-            .collect::<Vec<_>>()
-            .join(" ")
+            .collect()
+    }
+
+    /// Returns the thought summaries of the first candidate, if any.
+    ///
+    /// Thought summaries are only returned when `include_reasoning` is set
+    /// in the request's [`ReasoningOptions`].
+    ///
+    /// [`ReasoningOptions`]: latchlm_core::ReasoningOptions
+    #[must_use]
+    pub fn extract_reasoning(&self) -> Option<String> {
+        self.candidates.first()?.reasoning()
     }
+
+    /// Returns the normalized reason the first candidate stopped generating.
+    ///
+    /// Blocked prompts are reported as [`FinishReason::ContentFilter`].
+    #[must_use]
+    pub fn finish_reason(&self) -> Option<FinishReason> {
+        if self
+            .prompt_feedback
+            .as_ref()
+            .is_some_and(|feedback| feedback.block_reason.is_some())
+        {
+            return Some(FinishReason::ContentFilter);
+        }
+
+        self.candidates.first()?.finish_reason()
+    }
+
+    /// Returns the grounding sources of the first candidate, if a grounding tool was used.
+    #[must_use]
+    pub fn grounding_metadata(&self) -> Option<&GroundingMetadata> {
+        self.candidates.first()?.grounding_metadata.as_ref()
+    }
+
+    /// Returns the URLs retrieved for the first candidate by the URL context tool, if any.
+    #[must_use]
+    pub fn url_context_metadata(&self) -> Option<&UrlContextMetadata> {
+        self.candidates.first()?.url_context_metadata.as_ref()
+    }
+
+    /// Returns the response, or an error if the prompt or the first candidate was blocked.
+    ///
+    /// Blocked responses are otherwise returned as is, with an empty text and a
+    /// [`FinishReason::ContentFilter`] finish reason.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ContentBlockedError`] with the block reason reported by the API.
+    ///
+    /// [`Error::ContentBlockedError`]: latchlm_core::Error::ContentBlockedError
+    pub fn error_for_block(self) -> Result<Self> {
+        if let Some(reason) = self
+            .prompt_feedback
+            .as_ref()
+            .and_then(|feedback| feedback.block_reason.as_deref())
+        {
+            return Err(Error::ContentBlockedError(format!(
+                "Prompt blocked: {reason}"
+            )));
+        }
+
+        if let Some(candidate) = self.candidates.first()
+            && candidate.finish_reason() == Some(FinishReason::ContentFilter)
+        {
+            return Err(Error::ContentBlockedError(format!(
+                "Response blocked: {}",
+                candidate.finish_reason.as_deref().unwrap_or_default()
+            )));
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the prompt feedback and the safety ratings of the first candidate, if any
+    /// were reported.
+    ///
+    /// The ratings of the other candidates are available in [`Candidate::safety_ratings`].
+    #[must_use]
+    pub fn safety(&self) -> Option<SafetyInfo> {
+        let response_ratings: Vec<_> = self
+            .candidates
+            .first()
+            .map(|candidate| candidate.safety_ratings.iter().map(Into::into).collect())
+            .unwrap_or_default();
+
+        if self.prompt_feedback.is_none() && response_ratings.is_empty() {
+            return None;
+        }
+
+        let feedback = self.prompt_feedback.clone().unwrap_or_default();
+
+        Some(SafetyInfo {
+            prompt_block_reason: feedback.block_reason,
+            prompt_ratings: feedback.safety_ratings.iter().map(Into::into).collect(),
+            response_ratings,
+        })
+    }
+}
+
+/// Metadata of a model available through the Gemini API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiModelInfo {
+    /// The resource name of the model, e.g. `models/gemini-2.5-flash`
+    pub name: String,
+    pub version: Option<String>,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    /// The maximum number of input tokens, i.e. the context window
+    pub input_token_limit: Option<u64>,
+    /// The maximum number of output tokens
+    pub output_token_limit: Option<u64>,
+    /// The API methods supported by the model, e.g. `generateContent`
+    #[serde(default)]
+    pub supported_generation_methods: Vec<String>,
+    /// Whether the model supports thinking
+    pub thinking: Option<bool>,
+}
+
+impl GeminiModelInfo {
+    /// Returns the id of the model as used in requests, without the `models/` prefix.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        self.name.strip_prefix("models/").unwrap_or(&self.name)
+    }
+
+    /// Returns the model, as a known variant or as [`GeminiModel::Custom`].
+    #[must_use]
+    pub fn model(&self) -> GeminiModel {
+        GeminiModel::try_from(self.id()).unwrap_or_else(|_| GeminiModel::Custom(self.id().into()))
+    }
+}
+
+/// A page of the list of available models.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiModelsList {
+    #[serde(default)]
+    pub(crate) models: Vec<GeminiModelInfo>,
+    pub(crate) next_page_token: Option<String>,
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod test {
     use super::*;
 
@@ -105,11 +563,14 @@ mod test {
                         parts: vec![
                             Text {
                                 text: "First part.".to_string(),
+                                ..Default::default()
                             },
                             Text {
-                                text: "Second part.".to_string(),
+                                text: " Second part.".to_string(),
+                                ..Default::default()
                             },
                         ],
+                        ..Default::default()
                     },
                     ..Default::default()
                 },
@@ -117,7 +578,9 @@ mod test {
                     content: Content {
                         parts: vec![Text {
                             text: "Another candidate.".to_string(),
+                            ..Default::default()
                         }],
+                        ..Default::default()
                     },
                     ..Default::default()
                 },
@@ -125,9 +588,46 @@ mod test {
             ..Default::default()
         };
 
+        assert_eq!(test_response.extract_text(), "First part. Second part.");
+
+        let candidates = test_response.extract_candidates();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[1].index, 1);
+        assert_eq!(candidates[1].text, "Another candidate.");
+    }
+
+    #[test]
+    fn test_extract_candidates() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [
+                {"content": {"parts": [{"text": "Yes"}]}, "finishReason": "STOP", "index": 0},
+                {"content": {"parts": [{"text": "No"}]}, "finishReason": "MAX_TOKENS", "index": 1}
+            ],
+            "usageMetadata": {"promptTokenCount": 5, "candidatesTokenCount": 2, "totalTokenCount": 7},
+            "modelVersion": "gemini-2.5-flash",
+            "responseId": "abc"
+        }))
+        .unwrap();
+
+        let response = AiResponse::from(response);
+        assert_eq!(response.text, "Yes");
+        assert_eq!(response.finish_reason, Some(FinishReason::Stop));
         assert_eq!(
-            test_response.extract_text(),
-            "First part. Second part. Another candidate."
+            response.candidates,
+            vec![
+                latchlm_core::Candidate {
+                    index: 0,
+                    text: "Yes".to_string(),
+                    reasoning: None,
+                    finish_reason: Some(FinishReason::Stop),
+                },
+                latchlm_core::Candidate {
+                    index: 1,
+                    text: "No".to_string(),
+                    reasoning: None,
+                    finish_reason: Some(FinishReason::Length),
+                },
+            ]
         );
     }
 
@@ -142,7 +642,7 @@ mod test {
     fn test_extract_text_empty_parts() {
         let test_response = GeminiResponse {
             candidates: vec![Candidate {
-                content: Content { parts: vec![] },
+                content: Content::default(),
                 ..Default::default()
             }],
             ..Default::default()
@@ -150,4 +650,170 @@ mod test {
 
         assert_eq!(test_response.extract_text(), "");
     }
+
+    #[test]
+    fn test_extract_reasoning() {
+        let test_response = GeminiResponse {
+            candidates: vec![Candidate {
+                content: Content {
+                    parts: vec![
+                        Text {
+                            text: "Thinking about it.".to_string(),
+                            thought: Some(true),
+                            ..Default::default()
+                        },
+                        Text {
+                            text: "The answer.".to_string(),
+                            thought: Some(false),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(test_response.extract_text(), "The answer.");
+        assert_eq!(
+            test_response.extract_reasoning().as_deref(),
+            Some("Thinking about it.")
+        );
+        assert_eq!(GeminiResponse::default().extract_reasoning(), None);
+    }
+
+    #[test]
+    fn test_reasoning_tokens() {
+        let test_response = GeminiResponse {
+            usage_metadata: UsageMetadata {
+                thoughts_token_count: Some(42),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let response = AiResponse::from(test_response);
+        assert_eq!(response.token_usage.reasoning_tokens, Some(42));
+    }
+
+    #[test]
+    fn test_finish_reason_and_safety() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{
+                "content": {"parts": [{"text": "Partial"}], "role": "model"},
+                "finishReason": "MAX_TOKENS",
+                "safetyRatings": [
+                    {"category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE"}
+                ]
+            }, {
+                "content": {"parts": [{"text": "Other"}], "role": "model"},
+                "finishReason": "STOP",
+                "safetyRatings": [
+                    {"category": "HARM_CATEGORY_HATE_SPEECH", "probability": "LOW"}
+                ]
+            }],
+            "usageMetadata": {"promptTokenCount": 5, "candidatesTokenCount": 8, "totalTokenCount": 13},
+            "modelVersion": "gemini-2.5-flash",
+            "responseId": "abc"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.candidates()[1].safety_ratings()[0].category,
+            "HARM_CATEGORY_HATE_SPEECH"
+        );
+
+        let response = AiResponse::from(response);
+        assert_eq!(response.finish_reason, Some(FinishReason::Length));
+
+        let safety = response.safety.unwrap();
+        assert!(!safety.is_blocked());
+        assert_eq!(safety.response_ratings.len(), 1);
+        assert_eq!(
+            safety.response_ratings[0].category,
+            "HARM_CATEGORY_HARASSMENT"
+        );
+    }
+
+    #[test]
+    fn test_blocked_prompt() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "promptFeedback": {
+                "blockReason": "SAFETY",
+                "safetyRatings": [
+                    {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH", "blocked": true}
+                ]
+            },
+            "usageMetadata": {"promptTokenCount": 5, "totalTokenCount": 5},
+            "modelVersion": "gemini-2.5-flash",
+            "responseId": "abc"
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            response.clone().error_for_block(),
+            Err(Error::ContentBlockedError(reason)) if reason == "Prompt blocked: SAFETY"
+        ));
+
+        let response = AiResponse::from(response);
+        assert_eq!(response.text, "");
+        assert_eq!(response.finish_reason, Some(FinishReason::ContentFilter));
+
+        let safety = response.safety.unwrap();
+        assert!(safety.is_blocked());
+        assert_eq!(safety.prompt_block_reason.as_deref(), Some("SAFETY"));
+        assert!(safety.prompt_ratings[0].blocked);
+    }
+
+    #[test]
+    fn test_token_usage_details() {
+        let usage: UsageMetadata = serde_json::from_value(serde_json::json!({
+            "promptTokenCount": 300,
+            "candidatesTokenCount": 20,
+            "totalTokenCount": 340,
+            "cachedContentTokenCount": 256,
+            "thoughtsTokenCount": 20,
+            "promptTokensDetails": [
+                {"modality": "TEXT", "tokenCount": 42},
+                {"modality": "IMAGE", "tokenCount": 258}
+            ],
+            "candidatesTokensDetails": [
+                {"modality": "TEXT", "tokenCount": 20}
+            ]
+        }))
+        .unwrap();
+
+        let usage = TokenUsage::from(&usage);
+
+        assert_eq!(usage.output_tokens, Some(40));
+        assert_eq!(usage.cached_input_tokens, Some(256));
+        assert_eq!(usage.reasoning_tokens, Some(20));
+        assert_eq!(
+            usage.input_tokens_by_modality,
+            BTreeMap::from([(Modality::Text, 42), (Modality::Image, 258)])
+        );
+        assert_eq!(
+            usage.output_tokens_by_modality,
+            BTreeMap::from([(Modality::Text, 20)])
+        );
+    }
+
+    #[test]
+    fn test_token_usage_saturates() {
+        let usage: UsageMetadata = serde_json::from_value(serde_json::json!({
+            "candidatesTokenCount": u64::MAX,
+            "thoughtsTokenCount": 1,
+            "promptTokensDetails": [
+                {"modality": "TEXT", "tokenCount": u64::MAX},
+                {"modality": "TEXT", "tokenCount": 1}
+            ]
+        }))
+        .unwrap();
+
+        let usage = TokenUsage::from(&usage);
+
+        assert_eq!(usage.output_tokens, Some(u64::MAX));
+        assert_eq!(usage.input_tokens_by_modality[&Modality::Text], u64::MAX);
+    }
 }
@@ -0,0 +1,622 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! A client for the Gemini models served by Vertex AI.
+//!
+//! Vertex AI serves Gemini models from the regional endpoints of a Google Cloud project and
+//! authenticates with OAuth2 bearer tokens. Tokens are either provided directly, or minted
+//! from a service-account key by a [`ServiceAccountCredential`] and refreshed before they
+//! expire. Responses are parsed into the same [`GeminiResponse`] type as the [`Gemini`]
+//! client.
+//!
+//! [`Gemini`]: crate::Gemini
+
+use std::{
+    future::ready,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use futures::{FutureExt, StreamExt, stream::BoxStream};
+use latchlm_core::{
+    AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, CredentialProvider, Error, Result,
+    StaticCredential, join_url, parse_event_stream, parse_json, send_authenticated,
+};
+use ring::{
+    rand::SystemRandom,
+    signature::{RSA_PKCS1_SHA256, RsaKeyPair},
+};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+
+use crate::{
+    GeminiError, GeminiModel, GeminiRequestOptions, GeminiResponse, request::GeminiRequest,
+};
+
+/// The OAuth2 scope granting access to Vertex AI.
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// The grant type used to exchange a signed JWT for an access token.
+const JWT_BEARER_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+/// The token endpoint used when a service-account key does not name one.
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// The lifetime requested for minted tokens, the maximum Google allows.
+const TOKEN_LIFETIME_SECS: u64 = 3600;
+
+/// How long before its expiry a cached token is refreshed.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// The fields of a service-account key file used to mint access tokens.
+#[derive(Deserialize)]
+struct ServiceAccountFile {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+    #[serde(default)]
+    project_id: Option<String>,
+}
+
+/// A Google Cloud service-account key, used to mint OAuth2 access tokens.
+///
+/// The key is read from the JSON key file downloaded from the Google Cloud console.
+#[derive(Debug, Clone)]
+pub struct ServiceAccountKey {
+    client_email: String,
+    token_uri: reqwest::Url,
+    project_id: Option<String>,
+    key_pair: Arc<RsaKeyPair>,
+}
+
+impl ServiceAccountKey {
+    /// Parses a service-account key from the contents of its JSON key file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is not a service-account key, or its private key is not
+    /// a PEM-encoded PKCS#8 RSA key.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: ServiceAccountFile = serde_json::from_str(json)?;
+
+        let der = file
+            .private_key
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect::<String>();
+        let der = STANDARD
+            .decode(der.trim())
+            .map_err(|err| vertex_error(&format!("Invalid private key: {err}")))?;
+        let key_pair = RsaKeyPair::from_pkcs8(&der)
+            .map_err(|err| vertex_error(&format!("Invalid private key: {err}")))?;
+
+        let token_uri = file.token_uri.as_deref().unwrap_or(DEFAULT_TOKEN_URI);
+        let token_uri = reqwest::Url::parse(token_uri)
+            .map_err(|_| Error::InvalidUrlError(token_uri.to_owned()))?;
+
+        Ok(Self {
+            client_email: file.client_email,
+            token_uri,
+            project_id: file.project_id,
+            key_pair: Arc::new(key_pair),
+        })
+    }
+
+    /// Reads a service-account key from a JSON key file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid service-account key.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Reads the service-account key file named by the `GOOGLE_APPLICATION_CREDENTIALS`
+    /// environment variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variable is not set, or the file cannot be read or is not a
+    /// valid service-account key.
+    pub fn from_env() -> Result<Self> {
+        let path = std::env::var("GOOGLE_APPLICATION_CREDENTIALS").map_err(|_| {
+            vertex_error("Environment variable GOOGLE_APPLICATION_CREDENTIALS is not set")
+        })?;
+
+        Self::from_path(path)
+    }
+
+    /// Returns the email address of the service account.
+    #[must_use]
+    pub fn client_email(&self) -> &str {
+        &self.client_email
+    }
+
+    /// Returns the project the service account belongs to, if the key file names one.
+    #[must_use]
+    pub fn project_id(&self) -> Option<&str> {
+        self.project_id.as_deref()
+    }
+
+    /// Returns the endpoint the signed token requests are sent to.
+    #[must_use]
+    pub fn token_uri(&self) -> &reqwest::Url {
+        &self.token_uri
+    }
+
+    /// Creates the signed JWT exchanged for an access token.
+    fn assertion(&self) -> Result<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"RS256","typ":"JWT"}"#);
+        let claims = serde_json::json!({
+            "iss": self.client_email,
+            "scope": CLOUD_PLATFORM_SCOPE,
+            "aud": self.token_uri.as_str(),
+            "iat": now,
+            "exp": now + TOKEN_LIFETIME_SECS,
+        });
+        let claims = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?);
+        let message = format!("{header}.{claims}");
+
+        let mut signature = vec![0; self.key_pair.public().modulus_len()];
+        self.key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message.as_bytes(),
+                &mut signature,
+            )
+            .map_err(|_| vertex_error("Failed to sign the token request"))?;
+
+        Ok(format!("{message}.{}", URL_SAFE_NO_PAD.encode(signature)))
+    }
+}
+
+/// The credentials used to authenticate with Vertex AI.
+#[derive(Debug, Clone)]
+pub enum VertexAuth {
+    /// An OAuth2 access token, used until the client is dropped
+    AccessToken(SecretString),
+    /// A service-account key, used to mint access tokens and refresh them before they expire
+    ServiceAccount(ServiceAccountKey),
+    /// A provider consulted for an access token before every request
+    Credentials(Arc<dyn CredentialProvider>),
+}
+
+/// The body of a successful token response.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// An access token minted from a service-account key.
+#[derive(Debug)]
+struct CachedToken {
+    token: SecretString,
+    expires_at: Instant,
+}
+
+/// Access tokens minted from a service-account key.
+///
+/// A minted token is cached until a minute before it expires, or until it is invalidated
+/// after the API rejects it.
+#[derive(Debug)]
+pub struct ServiceAccountCredential {
+    client: reqwest::Client,
+    key: ServiceAccountKey,
+    cache: Mutex<Option<CachedToken>>,
+}
+
+impl ServiceAccountCredential {
+    /// Creates a provider minting tokens from `key`, requested with `client`.
+    #[must_use]
+    pub fn new(client: reqwest::Client, key: ServiceAccountKey) -> Self {
+        Self {
+            client,
+            key,
+            cache: Mutex::new(None),
+        }
+    }
+
+    fn cached(&self) -> Option<SecretString> {
+        let cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        let cached = cache.as_ref()?;
+
+        (cached.expires_at > Instant::now() + TOKEN_REFRESH_MARGIN).then(|| cached.token.clone())
+    }
+
+    async fn mint(&self) -> Result<SecretString> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Minting access token for {}", self.key.client_email);
+
+        let assertion = self.key.assertion()?;
+        let response = self
+            .client
+            .post(self.key.token_uri.clone())
+            .form(&[
+                ("grant_type", JWT_BEARER_GRANT_TYPE),
+                ("assertion", &assertion),
+            ])
+            .send()
+            .await?;
+
+        let response: TokenResponse = parse_json(response).await?;
+        let token = SecretString::from(response.access_token);
+
+        *self.cache.lock().unwrap_or_else(PoisonError::into_inner) = Some(CachedToken {
+            token: token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+
+        Ok(token)
+    }
+}
+
+impl CredentialProvider for ServiceAccountCredential {
+    fn credential(&self) -> BoxFuture<'_, Result<SecretString>> {
+        Box::pin(async move {
+            match self.cached() {
+                Some(token) => Ok(token),
+                None => self.mint().await,
+            }
+        })
+    }
+
+    fn invalidate(&self) {
+        *self.cache.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+/// A builder for creating a [`Vertex`] client.
+#[derive(Debug, Clone, Default)]
+pub struct VertexBuilder {
+    client: Option<reqwest::Client>,
+    project: Option<String>,
+    location: Option<String>,
+    auth: Option<VertexAuth>,
+    base_url: Option<reqwest::Url>,
+}
+
+impl VertexBuilder {
+    /// Create a new Vertex AI builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the HTTP client to use for making requests.
+    #[must_use]
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the Google Cloud project.
+    ///
+    /// Defaults to the project of the service-account key.
+    #[must_use]
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Set the location serving the models, such as `europe-west4` or `global`.
+    ///
+    /// Defaults to [`Vertex::DEFAULT_LOCATION`].
+    #[must_use]
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Authenticate with an OAuth2 access token.
+    #[must_use]
+    pub fn access_token(mut self, token: SecretString) -> Self {
+        self.auth = Some(VertexAuth::AccessToken(token));
+        self
+    }
+
+    /// Authenticate with tokens minted from a service-account key.
+    #[must_use]
+    pub fn service_account(mut self, key: ServiceAccountKey) -> Self {
+        self.auth = Some(VertexAuth::ServiceAccount(key));
+        self
+    }
+
+    /// Authenticate with access tokens from a provider consulted before every request.
+    #[must_use]
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.auth = Some(VertexAuth::Credentials(Arc::new(credentials)));
+        self
+    }
+
+    /// Loads the service-account key named by the `GOOGLE_APPLICATION_CREDENTIALS`
+    /// environment variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`ServiceAccountKey::from_env`].
+    pub fn service_account_from_env(self) -> Result<Self> {
+        Ok(self.service_account(ServiceAccountKey::from_env()?))
+    }
+
+    /// Set the base URL the API paths are appended to.
+    ///
+    /// Defaults to the endpoint of the location, such as
+    /// `https://europe-west4-aiplatform.googleapis.com`. Any path prefix is kept.
+    #[must_use]
+    pub fn base_url(mut self, base_url: reqwest::Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Build the Vertex AI client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client, credentials or project are missing, or the base URL
+    /// cannot be a base.
+    pub fn build(self) -> Result<Vertex> {
+        let client = self.client.ok_or(GeminiError::MissingClientError)?;
+        let auth = self.auth.ok_or(GeminiError::MissingCredentialsError)?;
+
+        let project = match (self.project, &auth) {
+            (Some(project), _) => project,
+            (None, VertexAuth::ServiceAccount(key)) => key
+                .project_id
+                .clone()
+                .ok_or(GeminiError::MissingProjectError)?,
+            (None, VertexAuth::AccessToken(_) | VertexAuth::Credentials(_)) => {
+                Err(GeminiError::MissingProjectError)?
+            }
+        };
+
+        let location = self
+            .location
+            .unwrap_or_else(|| Vertex::DEFAULT_LOCATION.to_owned());
+
+        let base_url = match self.base_url {
+            Some(base_url) => base_url,
+            None => {
+                let base_url = if location == "global" {
+                    "https://aiplatform.googleapis.com".to_owned()
+                } else {
+                    format!("https://{location}-aiplatform.googleapis.com")
+                };
+
+                reqwest::Url::parse(&base_url).map_err(|_| Error::InvalidUrlError(base_url))?
+            }
+        };
+
+        if base_url.cannot_be_a_base() {
+            return Err(Error::InvalidUrlError(base_url.to_string()));
+        }
+
+        let credentials: Arc<dyn CredentialProvider> = match auth {
+            VertexAuth::AccessToken(token) => Arc::new(StaticCredential::new(token)),
+            VertexAuth::ServiceAccount(key) => {
+                Arc::new(ServiceAccountCredential::new(client.clone(), key))
+            }
+            VertexAuth::Credentials(credentials) => credentials,
+        };
+
+        Ok(Vertex {
+            client,
+            base_url,
+            project,
+            location,
+            credentials,
+        })
+    }
+}
+
+/// A client for interacting with the Gemini models of Vertex AI.
+///
+/// Clones share the access token minted from a service-account key.
+#[derive(Debug, Clone)]
+pub struct Vertex {
+    client: reqwest::Client,
+    base_url: reqwest::Url,
+    project: String,
+    location: String,
+    credentials: Arc<dyn CredentialProvider>,
+}
+
+impl Vertex {
+    /// The location used when none is set.
+    pub const DEFAULT_LOCATION: &str = "us-central1";
+
+    /// Create a new [`VertexBuilder`] instance.
+    #[must_use]
+    pub fn builder() -> VertexBuilder {
+        VertexBuilder::new()
+    }
+
+    /// Returns the Google Cloud project requests are billed to.
+    #[must_use]
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    /// Returns the location serving the models.
+    #[must_use]
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    fn url(&self, model: &GeminiModel, method: &str) -> Result<reqwest::Url> {
+        join_url(
+            &self.base_url,
+            &format!(
+                "v1/projects/{}/locations/{}/publishers/google/models/{}:{method}",
+                self.project,
+                self.location,
+                model.as_ref()
+            ),
+        )
+    }
+
+    async fn post(
+        &self,
+        url: reqwest::Url,
+        request: AiRequest,
+        options: GeminiRequestOptions,
+    ) -> Result<reqwest::Response> {
+        let payload = GeminiRequest::from(request).with_options(options);
+
+        send_authenticated(&*self.credentials, |token| {
+            self.client
+                .post(url.clone())
+                .bearer_auth(token.expose_secret())
+                .json(&payload)
+        })
+        .await
+    }
+
+    /// Sends a request to the `generateContent` method of Vertex AI.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if:
+    /// - An access token cannot be minted from the service-account key
+    /// - The HTTP request fails (network issues, timeout, etc.)
+    /// - The API returns a non-success status code
+    /// - The response body cannot be parsed as valid JSON
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn request(&self, model: GeminiModel, request: AiRequest) -> Result<GeminiResponse> {
+        self.request_with_options(model, request, GeminiRequestOptions::default())
+            .await
+    }
+
+    /// Sends a request to the `generateContent` method of Vertex AI with Gemini-specific
+    /// options.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Vertex::request`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn request_with_options(
+        &self,
+        model: GeminiModel,
+        request: AiRequest,
+        options: GeminiRequestOptions,
+    ) -> Result<GeminiResponse> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
+        }
+
+        let url = self.url(&model, "generateContent")?;
+        let response = self.post(url, request, options).await?;
+
+        parse_json(response).await
+    }
+
+    /// Sends a request to the `streamGenerateContent` method of Vertex AI and returns a
+    /// stream of responses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if an access token cannot be minted, the HTTP request fails or
+    /// the API returns a non-success status code.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn streaming_request(
+        &self,
+        model: GeminiModel,
+        request: AiRequest,
+    ) -> Result<BoxStream<'_, Result<GeminiResponse>>> {
+        self.streaming_request_with_options(model, request, GeminiRequestOptions::default())
+            .await
+    }
+
+    /// Sends a request to the `streamGenerateContent` method of Vertex AI with
+    /// Gemini-specific options and returns a stream of responses.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Vertex::streaming_request`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn streaming_request_with_options(
+        &self,
+        model: GeminiModel,
+        request: AiRequest,
+        options: GeminiRequestOptions,
+    ) -> Result<BoxStream<'_, Result<GeminiResponse>>> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
+        }
+
+        let url = self.url(&model, "streamGenerateContent?alt=sse")?;
+        let response = self.post(url, request, options).await?;
+
+        parse_event_stream(response, "Gemini").await
+    }
+}
+
+impl AiProvider for Vertex {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, model)))]
+    fn send_request(
+        &self,
+        model: &dyn AiModel,
+        request: AiRequest,
+    ) -> BoxFuture<'_, Result<AiResponse>> {
+        let Some(model) = model.downcast::<GeminiModel>() else {
+            let model_name = model.as_ref().to_owned();
+
+            #[cfg(feature = "tracing")]
+            tracing::error!("Invalid model type: {}", model_name);
+
+            return Box::pin(ready(Err(Error::InvalidModelError(model_name))));
+        };
+
+        Box::pin(async move { self.request(model, request).await.map(Into::into) })
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, model)))]
+    fn send_streaming(
+        &self,
+        model: &dyn AiModel,
+        request: AiRequest,
+    ) -> BoxStream<'_, Result<AiResponse>> {
+        let Some(model) = model.downcast::<GeminiModel>() else {
+            let model_name = model.as_ref().to_owned();
+
+            #[cfg(feature = "tracing")]
+            tracing::error!("Invalid model type: {}", model_name);
+
+            return Box::pin(futures::stream::once(async move {
+                Err(Error::InvalidModelError(model_name))
+            }));
+        };
+
+        Box::pin(
+            async move {
+                match self.streaming_request(model, request).await {
+                    Ok(stream) => stream.map(|res| res.map(Into::into)).boxed(),
+                    Err(e) => futures::stream::once(async move { Err(e) }).boxed(),
+                }
+            }
+            .flatten_stream(),
+        )
+    }
+}
+
+fn vertex_error(error: &str) -> Error {
+    Error::ProviderError {
+        provider: "Vertex".to_owned(),
+        error: error.to_owned(),
+    }
+}
@@ -5,12 +5,23 @@
 #![allow(clippy::unwrap_used)]
 #![allow(clippy::expect_used)]
 
-use latchlm_core::{AiModel, AiProvider, AiRequest, Error};
-use latchlm_gemini::{Gemini, GeminiModel};
+use futures::StreamExt;
+use latchlm_core::{
+    AiModel, AiProvider, AiRequest, AiResponse, Citation, Error, PollOptions, ReasoningOptions,
+    StaticCredential,
+};
+use latchlm_gemini::{
+    CachedContentRequest, FileState, FileUpload, Gemini, GeminiModel, GeminiRequestOptions,
+    GeminiTool, HarmBlockThreshold, HarmCategory, SafetySetting,
+};
+use latchlm_test_support::exact_text;
 use secrecy::{ExposeSecret, SecretString};
+use std::time::Duration;
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{header, method, path_regex},
+    matchers::{
+        body_partial_json, body_string, header, headers, method, path, path_regex, query_param,
+    },
 };
 
 #[tokio::test]
@@ -67,6 +78,7 @@ async fn test_gemini_request_response() {
             &model,
             AiRequest {
                 text: "Test Message".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -110,6 +122,7 @@ async fn test_gemini_error_unhautenticated() {
             &model,
             AiRequest {
                 text: "Test message".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -144,6 +157,7 @@ async fn test_gemini_error_invalid_model() {
             latchlm_core::ModelId {
                 id: "invalid_model".into(),
                 name: "Invalid Model".into(),
+                ..Default::default()
             }
         }
     }
@@ -156,6 +170,7 @@ async fn test_gemini_error_invalid_model() {
 
     let request = AiRequest {
         text: "Test Request".to_owned(),
+        ..Default::default()
     };
 
     let err = gemini
@@ -170,3 +185,884 @@ async fn test_gemini_error_invalid_model() {
         _ => panic!("Expected InvalidModelError"),
     }
 }
+
+#[tokio::test]
+async fn test_gemini_reasoning_request() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL");
+
+    let mock_response_body = serde_json::json!({
+        "candidates": [
+            {
+                "content": {
+                    "parts": [
+                        {
+                            "text": "The user wants a greeting.",
+                            "thought": true
+                        },
+                        {
+                            "text": "Hello!"
+                        }
+                    ]
+                },
+                "finishReason": "STOP",
+                "index": 0
+            }
+        ],
+        "usageMetadata": {
+            "promptTokenCount": 4,
+            "candidatesTokenCount": 2,
+            "thoughtsTokenCount": 30,
+            "totalTokenCount": 36,
+            "promptTokensDetails": []
+        },
+        "modelVersion": "gemini-2.5-pro",
+        "responseId": ""
+    });
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path_regex(r".+:generateContent$"))
+        .and(body_partial_json(serde_json::json!({
+            "generationConfig": {
+                "thinkingConfig": {
+                    "thinkingBudget": 512,
+                    "includeThoughts": true
+                }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_response_body))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url,
+        SecretString::from("api-key"),
+    );
+
+    let response = test_client
+        .send_request(
+            &GeminiModel::Pro25,
+            AiRequest {
+                text: "Say hello".to_owned(),
+                reasoning: Some(ReasoningOptions {
+                    budget_tokens: Some(512),
+                    include_reasoning: true,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hello!");
+    assert_eq!(
+        response.reasoning.as_deref(),
+        Some("The user wants a greeting.")
+    );
+    assert_eq!(response.token_usage.reasoning_tokens, Some(30));
+}
+
+fn gemini_chunk(parts: &[&str]) -> serde_json::Value {
+    let parts = parts
+        .iter()
+        .map(|text| serde_json::json!({"text": text}))
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "candidates": [{"content": {"parts": parts, "role": "model"}, "index": 0}],
+        "usageMetadata": {"promptTokenCount": 0, "candidatesTokenCount": 0, "totalTokenCount": 0},
+        "modelVersion": "",
+        "responseId": ""
+    })
+}
+
+#[tokio::test]
+async fn test_gemini_preserves_exact_text() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL");
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path_regex(r".+:generateContent$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(gemini_chunk(&exact_text::PARTS)))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url,
+        SecretString::from("test_api_key"),
+    );
+
+    let response = test_client
+        .send_request(&GeminiModel::Flash25, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    exact_text::assert_preserved(&response.text);
+}
+
+#[tokio::test]
+async fn test_gemini_streaming_preserves_exact_text() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL");
+
+    let body = exact_text::PARTS
+        .iter()
+        .map(|part| format!("data: {}\r\n\r\n", gemini_chunk(&[part])))
+        .collect::<String>();
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path_regex(r".+:streamGenerateContent$"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url,
+        SecretString::from("test_api_key"),
+    );
+
+    let text = test_client
+        .send_streaming(&GeminiModel::Flash25, AiRequest::default())
+        .map(|chunk| chunk.expect("Failed to receive chunk").text)
+        .collect::<String>()
+        .await;
+
+    exact_text::assert_preserved(&text);
+}
+
+#[tokio::test]
+async fn test_gemini_models_endpoint() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL");
+
+    let test_api_key = SecretString::from("test_api_key");
+
+    let _second_page = Mock::given(method("GET"))
+        .and(path("/v1beta/models"))
+        .and(query_param("pageToken", "next"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "models": [{
+                "name": "models/gemini-3-pro-preview",
+                "version": "3-pro-preview",
+                "displayName": "Gemini 3 Pro Preview",
+                "inputTokenLimit": 1048576,
+                "outputTokenLimit": 65536,
+                "supportedGenerationMethods": ["generateContent", "countTokens"],
+                "thinking": true
+            }]
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _first_page = Mock::given(method("GET"))
+        .and(path("/v1beta/models"))
+        .and(header("x-goog-api-key", test_api_key.expose_secret()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "models": [{
+                "name": "models/gemini-2.5-flash",
+                "version": "001",
+                "displayName": "Gemini 2.5 Flash",
+                "inputTokenLimit": 1048576,
+                "outputTokenLimit": 65536,
+                "supportedGenerationMethods": ["generateContent"]
+            }],
+            "nextPageToken": "next"
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client =
+        Gemini::new_with_base_url(reqwest::Client::new(), mock_base_url, test_api_key);
+
+    let models = test_client.models().await.expect("Failed to list models");
+
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].model(), GeminiModel::Flash25);
+    assert_eq!(models[1].id(), "gemini-3-pro-preview");
+    assert_eq!(
+        models[1].model(),
+        GeminiModel::Custom("gemini-3-pro-preview".to_owned())
+    );
+    assert_eq!(models[1].input_token_limit, Some(1048576));
+    assert_eq!(models[1].thinking, Some(true));
+}
+
+#[tokio::test]
+async fn test_gemini_custom_model_request() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL");
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-3-pro-preview:generateContent"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(gemini_chunk(&["Hi"])))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url,
+        SecretString::from("test_api_key"),
+    );
+
+    let model: GeminiModel = "gemini-3-pro-preview".parse().unwrap();
+    let response = test_client
+        .send_request(&model, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hi");
+}
+
+#[tokio::test]
+async fn test_gemini_base_url_keeps_path_prefix() {
+    let mock_server = MockServer::start().await;
+    let base_url = reqwest::Url::parse(&format!("{}/proxy/gemini", mock_server.uri()))
+        .expect("Failed to parse URL");
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path(
+            "/proxy/gemini/v1beta/models/gemini-2.5-flash:generateContent",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(gemini_chunk(&["Hi"])))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(base_url)
+        .build()
+        .expect("Failed to build client");
+
+    let response = test_client
+        .send_request(&GeminiModel::Flash25, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hi");
+}
+
+#[test]
+fn test_gemini_builder_rejects_invalid_base_url() {
+    let result = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse("mailto:user@example.com").expect("Failed to parse URL"))
+        .build();
+
+    assert!(matches!(result, Err(Error::InvalidUrlError(_))));
+}
+
+#[tokio::test]
+async fn test_gemini_sends_provided_credentials() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(header("x-goog-api-key", "provided_api_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(gemini_chunk(&["Hi"])))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .credentials(StaticCredential::new(SecretString::from(
+            "provided_api_key",
+        )))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let response = test_client
+        .send_request(&GeminiModel::Flash25, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hi");
+}
+
+#[tokio::test]
+async fn test_gemini_google_search_grounding() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-2.5-flash:generateContent"))
+        .and(body_partial_json(serde_json::json!({
+            "tools": [{"googleSearch": {}}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "candidates": [
+                {
+                    "content": {
+                        "parts": [{"text": "Spain won Euro 2024."}],
+                        "role": "model"
+                    },
+                    "finishReason": "STOP",
+                    "index": 0,
+                    "groundingMetadata": {
+                        "webSearchQueries": ["who won euro 2024"],
+                        "searchEntryPoint": {"renderedContent": "<style></style>"},
+                        "groundingChunks": [
+                            {"web": {"uri": "https://example.com/uefa", "title": "uefa.com"}},
+                            {"web": {"uri": "https://example.com/wiki", "title": "wikipedia.org"}}
+                        ],
+                        "groundingSupports": [
+                            {
+                                "segment": {"endIndex": 20, "text": "Spain won Euro 2024."},
+                                "groundingChunkIndices": [0],
+                                "confidenceScores": [0.97]
+                            }
+                        ]
+                    }
+                }
+            ],
+            "usageMetadata": {
+                "promptTokenCount": 8,
+                "candidatesTokenCount": 6,
+                "totalTokenCount": 14
+            },
+            "modelVersion": "gemini-2.5-flash",
+            "responseId": "response-1"
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let options = GeminiRequestOptions {
+        tools: vec![GeminiTool::google_search()],
+        ..Default::default()
+    };
+
+    let response = test_client
+        .request_with_options(GeminiModel::Flash25, AiRequest::default(), options)
+        .await
+        .expect("Failed to send request");
+
+    let metadata = response
+        .grounding_metadata()
+        .expect("Missing grounding metadata");
+    assert_eq!(metadata.web_search_queries, ["who won euro 2024"]);
+    assert_eq!(
+        metadata
+            .search_entry_point
+            .as_ref()
+            .and_then(|entry_point| entry_point.rendered_content.as_deref()),
+        Some("<style></style>")
+    );
+    assert_eq!(metadata.grounding_supports[0].segment.start_index, 0);
+
+    assert_eq!(
+        AiResponse::from(response).citations,
+        [
+            Citation {
+                url: "https://example.com/uefa".into(),
+                title: Some("uefa.com".into()),
+                text: Some("Spain won Euro 2024.".into()),
+            },
+            Citation {
+                url: "https://example.com/wiki".into(),
+                title: Some("wikipedia.org".into()),
+                text: None,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_gemini_safety_settings_and_blocked_response() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-2.5-flash:generateContent"))
+        .and(body_partial_json(serde_json::json!({
+            "safetySettings": [
+                {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_LOW_AND_ABOVE"}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "candidates": [
+                {
+                    "finishReason": "SAFETY",
+                    "index": 0,
+                    "safetyRatings": [
+                        {
+                            "category": "HARM_CATEGORY_DANGEROUS_CONTENT",
+                            "probability": "LOW",
+                            "blocked": true
+                        }
+                    ]
+                }
+            ],
+            "usageMetadata": {"promptTokenCount": 8, "totalTokenCount": 8},
+            "modelVersion": "gemini-2.5-flash",
+            "responseId": "response-1"
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let options = GeminiRequestOptions {
+        safety_settings: vec![SafetySetting::new(
+            HarmCategory::DangerousContent,
+            HarmBlockThreshold::BlockLowAndAbove,
+        )],
+        ..Default::default()
+    };
+
+    let response = test_client
+        .request_with_options(GeminiModel::Flash25, AiRequest::default(), options)
+        .await
+        .expect("Failed to send request");
+
+    let safety = response.safety().expect("Missing safety information");
+    assert!(safety.is_blocked());
+    assert_eq!(safety.response_ratings[0].probability, "LOW");
+
+    assert!(matches!(
+        response.error_for_block(),
+        Err(Error::ContentBlockedError(reason)) if reason == "Response blocked: SAFETY"
+    ));
+}
+
+fn cached_content(expire_time: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": "cachedContents/handbook",
+        "model": "models/gemini-2.5-flash",
+        "displayName": "handbook",
+        "createTime": "2025-01-01T00:00:00Z",
+        "updateTime": "2025-01-01T00:00:00Z",
+        "expireTime": expire_time,
+        "usageMetadata": {"totalTokenCount": 4096}
+    })
+}
+
+#[tokio::test]
+async fn test_gemini_cached_content_lifecycle() {
+    let mock_server = MockServer::start().await;
+
+    let _create_guard = Mock::given(method("POST"))
+        .and(path("/v1beta/cachedContents"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "models/gemini-2.5-flash",
+            "systemInstruction": {"parts": [{"text": "Answer from the handbook."}]},
+            "ttl": "600s"
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(cached_content("2025-01-01T00:10:00Z")),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _second_page_guard = Mock::given(method("GET"))
+        .and(path("/v1beta/cachedContents"))
+        .and(query_param("pageToken", "next"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "cachedContents": [{"name": "cachedContents/other"}]
+        })))
+        .expect(1)
+        .with_priority(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _first_page_guard = Mock::given(method("GET"))
+        .and(path("/v1beta/cachedContents"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "cachedContents": [cached_content("2025-01-01T00:10:00Z")],
+            "nextPageToken": "next"
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _update_guard = Mock::given(method("PATCH"))
+        .and(path("/v1beta/cachedContents/handbook"))
+        .and(query_param("updateMask", "ttl"))
+        .and(body_partial_json(serde_json::json!({"ttl": "3600s"})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(cached_content("2025-01-01T01:00:00Z")),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _delete_guard = Mock::given(method("DELETE"))
+        .and(path("/v1beta/cachedContents/handbook"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let cached = test_client
+        .create_cached_content(
+            GeminiModel::Flash25,
+            CachedContentRequest {
+                system_instruction: Some("Answer from the handbook.".into()),
+                contents: vec!["The handbook".into()],
+                ttl: Some(Duration::from_secs(600)),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to create cached content");
+    assert_eq!(cached.name, "cachedContents/handbook");
+    assert_eq!(cached.usage_metadata.unwrap().total_token_count, 4096);
+
+    let listed = test_client
+        .list_cached_contents()
+        .await
+        .expect("Failed to list cached contents");
+    assert_eq!(
+        listed
+            .iter()
+            .map(|cached| cached.name.as_str())
+            .collect::<Vec<_>>(),
+        ["cachedContents/handbook", "cachedContents/other"]
+    );
+
+    let updated = test_client
+        .update_cached_content_ttl(&cached.name, Duration::from_secs(3600))
+        .await
+        .expect("Failed to update cached content");
+    assert_eq!(updated.expire_time.as_deref(), Some("2025-01-01T01:00:00Z"));
+
+    test_client
+        .delete_cached_content(&cached.name)
+        .await
+        .expect("Failed to delete cached content");
+}
+
+#[tokio::test]
+async fn test_gemini_request_with_cached_content() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path(
+            "/v1beta/models/gemini-2.5-flash:streamGenerateContent",
+        ))
+        .and(body_partial_json(serde_json::json!({
+            "cachedContent": "cachedContents/handbook"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            format!(
+                "data: {}\r\n\r\n",
+                serde_json::json!({
+                    "candidates": [
+                        {
+                            "content": {"parts": [{"text": "Page 3."}], "role": "model"},
+                            "finishReason": "STOP",
+                            "index": 0
+                        }
+                    ],
+                    "usageMetadata": {
+                        "promptTokenCount": 4106,
+                        "candidatesTokenCount": 3,
+                        "totalTokenCount": 4109,
+                        "cachedContentTokenCount": 4096
+                    },
+                    "modelVersion": "gemini-2.5-flash",
+                    "responseId": "response-1"
+                })
+            ),
+            "text/event-stream",
+        ))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let options = GeminiRequestOptions {
+        cached_content: Some("cachedContents/handbook".into()),
+        ..Default::default()
+    };
+
+    let chunks = test_client
+        .streaming_request_with_options(GeminiModel::Flash25, AiRequest::default(), options)
+        .await
+        .expect("Failed to send request")
+        .map(|chunk| AiResponse::from(chunk.expect("Failed to receive chunk")))
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(chunks[0].text, "Page 3.");
+    assert_eq!(chunks[0].token_usage.cached_input_tokens, Some(4096));
+}
+
+fn uploaded_file(state: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": "files/report",
+        "displayName": "report",
+        "mimeType": "application/pdf",
+        "sizeBytes": "9437184",
+        "uri": "https://generativelanguage.googleapis.com/v1beta/files/report",
+        "state": state
+    })
+}
+
+#[tokio::test]
+async fn test_gemini_upload_file_in_chunks() {
+    const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+    let mock_server = MockServer::start().await;
+    let upload_url = format!("{}/resumable/upload-1", mock_server.uri());
+
+    let _start_guard = Mock::given(method("POST"))
+        .and(path("/upload/v1beta/files"))
+        .and(header("X-Goog-Upload-Protocol", "resumable"))
+        .and(header("X-Goog-Upload-Command", "start"))
+        .and(header(
+            "X-Goog-Upload-Header-Content-Type",
+            "application/pdf",
+        ))
+        .and(header("X-Goog-Upload-Header-Content-Length", "9437184"))
+        .and(body_partial_json(
+            serde_json::json!({"file": {"display_name": "report"}}),
+        ))
+        .respond_with(ResponseTemplate::new(200).insert_header("X-Goog-Upload-URL", upload_url))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _first_chunk_guard = Mock::given(method("POST"))
+        .and(path("/resumable/upload-1"))
+        .and(header("X-Goog-Upload-Command", "upload"))
+        .and(header("X-Goog-Upload-Offset", "0"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _last_chunk_guard = Mock::given(method("POST"))
+        .and(path("/resumable/upload-1"))
+        .and(headers("X-Goog-Upload-Command", vec!["upload", "finalize"]))
+        .and(header(
+            "X-Goog-Upload-Offset",
+            CHUNK_SIZE.to_string().as_str(),
+        ))
+        .and(body_string("b".repeat(1024 * 1024)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"file": uploaded_file("PROCESSING")})),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let mut content = vec![b'a'; CHUNK_SIZE];
+    content.extend(vec![b'b'; 1024 * 1024]);
+
+    let file = test_client
+        .upload_file(
+            content.as_slice(),
+            FileUpload {
+                mime_type: "application/pdf".into(),
+                display_name: Some("report".into()),
+                size: Some(content.len() as u64),
+            },
+        )
+        .await
+        .expect("Failed to upload file");
+
+    assert_eq!(file.name, "files/report");
+    assert_eq!(file.size(), Some(9_437_184));
+    assert_eq!(file.state, FileState::Processing);
+
+    let requests = mock_server.received_requests().await.unwrap();
+    assert_eq!(requests[1].body.len(), CHUNK_SIZE);
+}
+
+#[tokio::test]
+async fn test_gemini_file_lifecycle() {
+    let mock_server = MockServer::start().await;
+
+    let _processing_guard = Mock::given(method("GET"))
+        .and(path("/v1beta/files/report"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(uploaded_file("PROCESSING")))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _active_guard = Mock::given(method("GET"))
+        .and(path("/v1beta/files/report"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(uploaded_file("ACTIVE")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _list_guard = Mock::given(method("GET"))
+        .and(path("/v1beta/files"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "files": [uploaded_file("ACTIVE")]
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _request_guard = Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-2.5-flash:generateContent"))
+        .and(body_partial_json(serde_json::json!({
+            "contents": [{
+                "parts": [
+                    {
+                        "fileData": {
+                            "mimeType": "application/pdf",
+                            "fileUri": "https://generativelanguage.googleapis.com/v1beta/files/report"
+                        }
+                    },
+                    {"text": "Summarize"}
+                ]
+            }]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(gemini_chunk(&["A report."])))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _delete_guard = Mock::given(method("DELETE"))
+        .and(path("/v1beta/files/report"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let poll = PollOptions {
+        interval: Duration::from_millis(10),
+        timeout: Some(Duration::from_secs(5)),
+    };
+    let file = test_client
+        .wait_for_file("files/report", poll)
+        .await
+        .expect("Failed to wait for file");
+    assert_eq!(file.state, FileState::Active);
+
+    let files = test_client
+        .list_files()
+        .await
+        .expect("Failed to list files");
+    assert_eq!(files, std::slice::from_ref(&file));
+
+    let options = GeminiRequestOptions {
+        files: vec![file.file_data()],
+        ..Default::default()
+    };
+    let response = test_client
+        .request_with_options(
+            GeminiModel::Flash25,
+            AiRequest {
+                text: "Summarize".into(),
+                ..Default::default()
+            },
+            options,
+        )
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.extract_text(), "A report.");
+
+    test_client
+        .delete_file(&file.name)
+        .await
+        .expect("Failed to delete file");
+}
+
+#[tokio::test]
+async fn test_gemini_wait_for_failed_file() {
+    let mock_server = MockServer::start().await;
+
+    let mut failed = uploaded_file("FAILED");
+    failed["error"] = serde_json::json!({"code": 3, "message": "Unsupported video codec"});
+
+    let _mock_guard = Mock::given(method("GET"))
+        .and(path("/v1beta/files/report"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(failed))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Gemini::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let result = test_client
+        .wait_for_file("files/report", PollOptions::default())
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::ProviderError { error, .. }) if error == "Unsupported video codec"
+    ));
+
+    for (name, state) in [
+        ("unspecified", "STATE_UNSPECIFIED"),
+        ("archived", "ARCHIVED"),
+    ] {
+        let _state_guard = Mock::given(method("GET"))
+            .and(path(format!("/v1beta/files/{name}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(uploaded_file(state)))
+            .expect(1)
+            .mount_as_scoped(&mock_server)
+            .await;
+
+        let result = test_client
+            .wait_for_file(&format!("files/{name}"), PollOptions::default())
+            .await;
+
+        assert!(matches!(result, Err(Error::ProviderError { .. })));
+    }
+}
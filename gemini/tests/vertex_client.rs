@@ -0,0 +1,311 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use futures::StreamExt;
+use latchlm_core::{AiProvider, AiRequest, Error};
+use latchlm_gemini::{GeminiModel, ServiceAccountKey, Vertex};
+use ring::signature::{KeyPair, RSA_PKCS1_2048_8192_SHA256, RsaKeyPair, UnparsedPublicKey};
+use secrecy::SecretString;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{bearer_token, body_string_contains, method, path},
+};
+
+// A throwaway RSA key generated for these tests only
+const PRIVATE_KEY: &str = include_str!("fixtures/service_account_key.pem");
+
+const GENERATE_CONTENT_PATH: &str = "/v1/projects/test-project/locations/us-central1/publishers/google/models/gemini-2.5-flash:generateContent";
+
+fn service_account_key(mock_server: &MockServer) -> ServiceAccountKey {
+    let json = serde_json::json!({
+        "type": "service_account",
+        "project_id": "test-project",
+        "private_key_id": "test-key-id",
+        "private_key": PRIVATE_KEY,
+        "client_email": "test@test-project.iam.gserviceaccount.com",
+        "token_uri": format!("{}/token", mock_server.uri()),
+    });
+
+    ServiceAccountKey::from_json(&json.to_string()).expect("Failed to parse service account key")
+}
+
+fn token_response(expires_in: u64) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "access_token": "minted-token",
+        "expires_in": expires_in,
+        "token_type": "Bearer"
+    }))
+}
+
+fn response_body(text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "candidates": [
+            {
+                "content": {"parts": [{"text": text}], "role": "model"},
+                "finishReason": "STOP",
+                "index": 0
+            }
+        ],
+        "usageMetadata": {
+            "promptTokenCount": 1,
+            "candidatesTokenCount": 1,
+            "totalTokenCount": 2
+        },
+        "modelVersion": "gemini-2.5-flash",
+        "responseId": "response-1"
+    })
+}
+
+#[tokio::test]
+async fn test_vertex_mints_and_reuses_token() {
+    let mock_server = MockServer::start().await;
+
+    let _token_guard = Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer",
+        ))
+        .respond_with(token_response(3600))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path(GENERATE_CONTENT_PATH))
+        .and(bearer_token("minted-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body("Hello")))
+        .expect(2)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = Vertex::builder()
+        .client(reqwest::Client::new())
+        .service_account(service_account_key(&mock_server))
+        .base_url(mock_server.uri().parse().expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    assert_eq!(client.project(), "test-project");
+
+    for _ in 0..2 {
+        let response = client
+            .send_request(&GeminiModel::Flash25, AiRequest::default())
+            .await
+            .expect("Failed to send request");
+
+        assert_eq!(response.text, "Hello");
+    }
+
+    let token_request = &mock_server.received_requests().await.unwrap()[0];
+    let body = String::from_utf8(token_request.body.clone()).unwrap();
+    let assertion = body
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("assertion="))
+        .expect("Missing assertion");
+
+    let (message, signature) = assertion.rsplit_once('.').expect("Missing signature");
+    let der = PRIVATE_KEY
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<String>();
+    let key_pair = RsaKeyPair::from_pkcs8(&STANDARD.decode(der).unwrap()).unwrap();
+    UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, key_pair.public_key().as_ref())
+        .verify(
+            message.as_bytes(),
+            &URL_SAFE_NO_PAD.decode(signature).unwrap(),
+        )
+        .expect("Invalid signature");
+
+    let claims = message.split('.').nth(1).expect("Missing claims");
+    let claims: serde_json::Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+
+    assert_eq!(claims["iss"], "test@test-project.iam.gserviceaccount.com");
+    assert_eq!(claims["aud"], format!("{}/token", mock_server.uri()));
+    assert_eq!(
+        claims["scope"],
+        "https://www.googleapis.com/auth/cloud-platform"
+    );
+}
+
+#[tokio::test]
+async fn test_vertex_refreshes_expiring_token() {
+    let mock_server = MockServer::start().await;
+
+    // Tokens expiring within a minute are refreshed before every request
+    let _token_guard = Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token_response(30))
+        .expect(2)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path(GENERATE_CONTENT_PATH))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body("Hello")))
+        .expect(2)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = Vertex::builder()
+        .client(reqwest::Client::new())
+        .service_account(service_account_key(&mock_server))
+        .base_url(mock_server.uri().parse().expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    for _ in 0..2 {
+        client
+            .send_request(&GeminiModel::Flash25, AiRequest::default())
+            .await
+            .expect("Failed to send request");
+    }
+}
+
+#[tokio::test]
+async fn test_vertex_remints_rejected_token() {
+    let mock_server = MockServer::start().await;
+
+    let _stale_token_guard = Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "revoked-token",
+            "expires_in": 3600
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _token_guard = Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token_response(3600))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _rejected_guard = Mock::given(method("POST"))
+        .and(path(GENERATE_CONTENT_PATH))
+        .and(bearer_token("revoked-token"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Token revoked"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path(GENERATE_CONTENT_PATH))
+        .and(bearer_token("minted-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body("Hello")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = Vertex::builder()
+        .client(reqwest::Client::new())
+        .service_account(service_account_key(&mock_server))
+        .base_url(mock_server.uri().parse().expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let response = client
+        .send_request(&GeminiModel::Flash25, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hello");
+}
+
+#[tokio::test]
+async fn test_vertex_token_error() {
+    let mock_server = MockServer::start().await;
+
+    let _token_guard = Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("invalid_grant"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = Vertex::builder()
+        .client(reqwest::Client::new())
+        .service_account(service_account_key(&mock_server))
+        .base_url(mock_server.uri().parse().expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let result = client
+        .send_request(&GeminiModel::Flash25, AiRequest::default())
+        .await;
+
+    assert!(matches!(result, Err(Error::ApiError { status: 400, .. })));
+}
+
+#[tokio::test]
+async fn test_vertex_streaming_request_with_access_token() {
+    let mock_server = MockServer::start().await;
+
+    let body = ["Hel", "lo"]
+        .iter()
+        .map(|text| format!("data: {}\r\n\r\n", response_body(text)))
+        .collect::<String>();
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path(
+            "/prefix/v1/projects/other-project/locations/global/publishers/google/models/gemini-2.5-pro:streamGenerateContent",
+        ))
+        .and(bearer_token("access-token"))
+        .and(body_string_contains(r#""role":"user""#))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = Vertex::builder()
+        .client(reqwest::Client::new())
+        .access_token(SecretString::from("access-token"))
+        .project("other-project")
+        .location("global")
+        .base_url(
+            format!("{}/prefix", mock_server.uri())
+                .parse()
+                .expect("Failed to parse URL"),
+        )
+        .build()
+        .expect("Failed to build client");
+
+    let text = client
+        .send_streaming(&GeminiModel::Pro25, AiRequest::default())
+        .map(|chunk| chunk.expect("Failed to receive chunk").text)
+        .collect::<String>()
+        .await;
+
+    assert_eq!(text, "Hello");
+}
+
+#[test]
+fn test_vertex_builder_errors() {
+    let result = Vertex::builder()
+        .client(reqwest::Client::new())
+        .access_token(SecretString::from("access-token"))
+        .build();
+    assert!(matches!(result, Err(Error::ProviderError { .. })));
+
+    let result = Vertex::builder()
+        .client(reqwest::Client::new())
+        .project("test-project")
+        .build();
+    assert!(matches!(result, Err(Error::ProviderError { .. })));
+
+    let result = ServiceAccountKey::from_json(
+        r#"{"client_email": "test@example.com", "private_key": "not a key"}"#,
+    );
+    assert!(matches!(result, Err(Error::ProviderError { .. })));
+}
@@ -0,0 +1,375 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Configuration-driven construction of providers.
+//!
+//! A [`Config`] describes the providers of a deployment and is deserialized with serde,
+//! so it can be written in any format serde supports, such as TOML, JSON or YAML.
+//! [`Config::from_json_path`] loads it from a JSON file, and
+//! [`ModelRegistry::from_config`] then builds every configured provider.
+//!
+//! API keys are never written in the configuration: each provider names the environment
+//! variable holding its key.
+//!
+//! ```json
+//! {
+//!     "default_model": "gemini:gemini-2.5-flash",
+//!     "providers": {
+//!         "gemini": {
+//!             "api_key_env": "GEMINI_API_KEY",
+//!             "base_url": "https://gateway.example.com/gemini",
+//!             "timeout_secs": 30
+//!         },
+//!         "router": {
+//!             "kind": "openrouter",
+//!             "api_key_env": "OPENROUTER_API_KEY",
+//!             "default_model": "anthropic/claude-3.5-sonnet",
+//!             "headers": { "X-Title": "My App" }
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use latchlm_core::{Error, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+
+use crate::ModelRegistry;
+
+/// The configuration of a set of providers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The providers, keyed by the prefix they are registered under
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderConfig>,
+    /// The provider-qualified model used when none is specified
+    #[serde(default)]
+    pub default_model: Option<String>,
+}
+
+impl Config {
+    /// Loads a configuration from a JSON file.
+    ///
+    /// Configurations in other formats can be deserialized with the matching serde crate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid JSON configuration.
+    pub fn from_json_path(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// The configuration of a single provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderConfig {
+    /// The kind of provider, inferred from its prefix if not set
+    #[serde(default)]
+    pub kind: Option<ProviderKind>,
+    /// The environment variable holding the API key
+    pub api_key_env: String,
+    /// The base URL the API paths are appended to, replacing the provider's default
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The timeout of every request, in seconds
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// The model used when only the provider prefix is given
+    #[serde(default)]
+    pub default_model: Option<String>,
+}
+
+/// The first-party providers that can be configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ProviderKind {
+    Gemini,
+    Openai,
+    Openrouter,
+}
+
+impl ProviderKind {
+    /// Returns the kind whose default prefix is `prefix`.
+    #[must_use]
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            ModelRegistry::GEMINI => Some(Self::Gemini),
+            ModelRegistry::OPENAI => Some(Self::Openai),
+            ModelRegistry::OPENROUTER => Some(Self::Openrouter),
+            _ => None,
+        }
+    }
+}
+
+impl ModelRegistry {
+    /// Builds every provider of `config` and registers it under its prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ProviderError`] if a provider's kind cannot be inferred or its cargo
+    /// feature is disabled, its API key variable is not set, or one of its headers is invalid.
+    /// Returns [`Error::InvalidModelError`] if a default model is not a valid model id.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut registry = Self::new();
+
+        for (prefix, provider) in &config.providers {
+            registry.register_config(prefix, provider)?;
+
+            if let Some(model_id) = &provider.default_model {
+                registry.set_provider_default_model(prefix, model_id)?;
+            }
+        }
+
+        if let Some(qualified) = &config.default_model {
+            registry.set_default_model(qualified)?;
+        }
+
+        Ok(registry)
+    }
+
+    #[cfg_attr(
+        not(any(feature = "gemini", feature = "openai", feature = "openrouter")),
+        allow(unused_variables)
+    )]
+    fn register_config(&mut self, prefix: &str, config: &ProviderConfig) -> Result<()> {
+        let kind = config
+            .kind
+            .or_else(|| ProviderKind::from_prefix(prefix))
+            .ok_or_else(|| provider_error(prefix, "Missing provider kind"))?;
+
+        let connection = Connection::new(prefix, config)?;
+
+        match kind {
+            #[cfg(feature = "gemini")]
+            ProviderKind::Gemini => {
+                let mut builder = crate::gemini::Gemini::builder()
+                    .client(connection.client)
+                    .api_key(connection.api_key);
+                if let Some(base_url) = connection.base_url {
+                    builder = builder.base_url(base_url);
+                }
+                let gemini = builder.build()?;
+
+                self.register::<crate::gemini::GeminiModel>(prefix, std::sync::Arc::new(gemini));
+                Ok(())
+            }
+            #[cfg(feature = "openai")]
+            ProviderKind::Openai => {
+                let mut builder = crate::openai::Openai::builder()
+                    .client(connection.client)
+                    .api_key(connection.api_key);
+                if let Some(base_url) = connection.base_url {
+                    builder = builder.base_url(base_url);
+                }
+                let openai = builder.build()?;
+
+                self.register::<crate::openai::OpenaiModel>(prefix, std::sync::Arc::new(openai));
+                Ok(())
+            }
+            #[cfg(feature = "openrouter")]
+            ProviderKind::Openrouter => {
+                let mut builder = crate::openrouter::Openrouter::builder()
+                    .client(connection.client)
+                    .api_key(connection.api_key);
+                if let Some(base_url) = connection.base_url {
+                    builder = builder.base_url(base_url);
+                }
+                let openrouter = builder.build()?;
+
+                self.register::<crate::openrouter::OpenrouterModel>(
+                    prefix,
+                    std::sync::Arc::new(openrouter),
+                );
+                Ok(())
+            }
+            #[allow(unreachable_patterns)]
+            kind => Err(provider_error(
+                prefix,
+                &format!("The {kind:?} provider requires its cargo feature to be enabled"),
+            )),
+        }
+    }
+}
+
+/// The settings shared by every kind of provider.
+#[cfg_attr(
+    not(any(feature = "gemini", feature = "openai", feature = "openrouter")),
+    allow(dead_code)
+)]
+struct Connection {
+    client: reqwest::Client,
+    api_key: SecretString,
+    base_url: Option<reqwest::Url>,
+}
+
+impl Connection {
+    /// Reads the API key of a provider, parses its base URL and builds its HTTP client.
+    fn new(prefix: &str, config: &ProviderConfig) -> Result<Self> {
+        let api_key = std::env::var(&config.api_key_env).map_err(|_| {
+            provider_error(
+                prefix,
+                &format!("Environment variable {} is not set", config.api_key_env),
+            )
+        })?;
+
+        let base_url = config
+            .base_url
+            .as_deref()
+            .map(reqwest::Url::parse)
+            .transpose()
+            .map_err(|err| provider_error(prefix, &format!("Invalid base URL: {err}")))?;
+
+        Ok(Self {
+            client: http_client(prefix, config)?,
+            api_key: SecretString::from(api_key),
+            base_url,
+        })
+    }
+}
+
+fn http_client(prefix: &str, config: &ProviderConfig) -> Result<reqwest::Client> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        let name = HeaderName::try_from(name.as_str())
+            .map_err(|err| provider_error(prefix, &format!("Invalid header name {name}: {err}")))?;
+        let value = HeaderValue::try_from(value.as_str())
+            .map_err(|err| provider_error(prefix, &format!("Invalid value for {name}: {err}")))?;
+
+        headers.insert(name, value);
+    }
+
+    let mut builder = reqwest::Client::builder().default_headers(headers);
+    if let Some(timeout) = config.timeout_secs {
+        builder = builder.timeout(Duration::from_secs(timeout));
+    }
+
+    Ok(builder.build()?)
+}
+
+fn provider_error(prefix: &str, error: &str) -> Error {
+    Error::ProviderError {
+        provider: prefix.to_owned(),
+        error: error.to_owned(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_from_json() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "default_model": "gemini:gemini-2.5-flash",
+                "providers": {
+                    "gemini": { "api_key_env": "GEMINI_API_KEY", "timeout_secs": 30 },
+                    "router": {
+                        "kind": "openrouter",
+                        "api_key_env": "OPENROUTER_API_KEY",
+                        "headers": { "X-Title": "My App" }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.providers["gemini"].kind, None);
+        assert_eq!(config.providers["gemini"].timeout_secs, Some(30));
+        assert_eq!(
+            config.providers["router"].kind,
+            Some(ProviderKind::Openrouter)
+        );
+        assert_eq!(config.providers["router"].headers["X-Title"], "My App");
+    }
+
+    #[test]
+    fn test_config_rejects_inline_api_keys() {
+        let config = serde_json::from_str::<Config>(
+            r#"{ "providers": { "gemini": { "api_key": "secret", "api_key_env": "KEY" } } }"#,
+        );
+
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_from_config_errors() {
+        let config = |provider: ProviderConfig| Config {
+            providers: BTreeMap::from([("custom".to_owned(), provider)]),
+            default_model: None,
+        };
+        let provider = ProviderConfig {
+            kind: None,
+            api_key_env: "PATH".to_owned(),
+            base_url: None,
+            headers: BTreeMap::new(),
+            timeout_secs: None,
+            default_model: None,
+        };
+
+        let result = ModelRegistry::from_config(&config(provider.clone()));
+        assert!(
+            matches!(result, Err(Error::ProviderError { provider, .. }) if provider == "custom")
+        );
+
+        let result = ModelRegistry::from_config(&config(ProviderConfig {
+            kind: Some(ProviderKind::Gemini),
+            api_key_env: "LATCHLM_TEST_UNSET_VARIABLE".to_owned(),
+            ..provider.clone()
+        }));
+        assert!(matches!(result, Err(Error::ProviderError { .. })));
+
+        let result = ModelRegistry::from_config(&config(ProviderConfig {
+            kind: Some(ProviderKind::Gemini),
+            headers: BTreeMap::from([("Invalid Header".to_owned(), "value".to_owned())]),
+            ..provider.clone()
+        }));
+        assert!(matches!(result, Err(Error::ProviderError { .. })));
+
+        let result = ModelRegistry::from_config(&config(ProviderConfig {
+            kind: Some(ProviderKind::Gemini),
+            base_url: Some("not a url".to_owned()),
+            ..provider
+        }));
+        assert!(matches!(result, Err(Error::ProviderError { .. })));
+    }
+
+    #[cfg(feature = "gemini")]
+    #[test]
+    fn test_from_config_builds_providers() {
+        let config = Config {
+            providers: BTreeMap::from([(
+                "gemini".to_owned(),
+                ProviderConfig {
+                    kind: None,
+                    api_key_env: "PATH".to_owned(),
+                    base_url: Some("https://gateway.example.com/gemini".to_owned()),
+                    headers: BTreeMap::from([("X-Custom".to_owned(), "value".to_owned())]),
+                    timeout_secs: Some(5),
+                    default_model: Some("gemini-2.5-pro".to_owned()),
+                },
+            )]),
+            default_model: Some("gemini:gemini-2.0-flash".to_owned()),
+        };
+
+        let registry = ModelRegistry::from_config(&config).unwrap();
+
+        let (_, model) = registry.resolve("gemini").unwrap();
+        assert_eq!(model.model_id().id, "gemini-2.5-pro");
+
+        let (_, model) = registry.default_model().unwrap();
+        assert_eq!(model.model_id().id, "gemini-2.0-flash");
+    }
+}
@@ -10,6 +10,12 @@
 
 pub use latchlm_core::*;
 
+pub mod config;
+pub use config::{Config, ProviderConfig, ProviderKind};
+
+pub mod registry;
+pub use registry::ModelRegistry;
+
 #[cfg(feature = "gemini")]
 pub use latchlm_gemini as gemini;
 
@@ -0,0 +1,399 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Resolution of provider-qualified model strings such as `gemini:gemini-2.5-flash`.
+
+use latchlm_core::{AiModel, AiProvider, Error, Result};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+/// Parses a model id into the model type of a provider.
+type ModelParser = fn(&str) -> Result<Box<dyn AiModel>>;
+
+#[derive(Clone)]
+struct RegistryEntry {
+    provider: Arc<dyn AiProvider>,
+    parse_model: ModelParser,
+    default_model: Option<String>,
+}
+
+/// A registry mapping provider prefixes to constructed providers.
+///
+/// The registry resolves provider-qualified model strings, in the form `provider:model`,
+/// into the provider to send requests to and the model to request.
+///
+/// Providers are registered with [`ModelRegistry::register`], or with the `with_*` methods of the
+/// enabled provider features.
+///
+/// # Example
+/// ```
+/// use latchlm::{AiRequest, ModelRegistry};
+///
+/// async fn ask(registry: &ModelRegistry, qualified_model: &str) -> latchlm::Result<String> {
+///     let (provider, model) = registry.resolve(qualified_model)?;
+///
+///     let request = AiRequest {
+///         text: "Hello".into(),
+///         ..Default::default()
+///     };
+///     let response = provider.send_request(&*model, request).await?;
+///
+///     Ok(response.text)
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ModelRegistry {
+    entries: HashMap<String, RegistryEntry>,
+    default_model: Option<String>,
+}
+
+impl ModelRegistry {
+    /// The prefix used for the Gemini provider
+    pub const GEMINI: &str = "gemini";
+    /// The prefix used for the OpenAI provider
+    pub const OPENAI: &str = "openai";
+    /// The prefix used for the OpenRouter provider
+    pub const OPENROUTER: &str = "openrouter";
+
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a provider under `prefix`, parsing its model ids into `M`.
+    ///
+    /// Returns the provider previously registered under the same prefix, if any.
+    pub fn register<M>(
+        &mut self,
+        prefix: impl Into<String>,
+        provider: Arc<dyn AiProvider>,
+    ) -> Option<Arc<dyn AiProvider>>
+    where
+        M: AiModel + FromStr<Err = Error>,
+    {
+        let entry = RegistryEntry {
+            provider,
+            parse_model: parse_model::<M>,
+            default_model: None,
+        };
+
+        self.entries
+            .insert(prefix.into(), entry)
+            .map(|previous| previous.provider)
+    }
+
+    /// Registers a provider under `prefix`, parsing its model ids into `M`.
+    #[must_use]
+    pub fn with_provider<M>(
+        mut self,
+        prefix: impl Into<String>,
+        provider: Arc<dyn AiProvider>,
+    ) -> Self
+    where
+        M: AiModel + FromStr<Err = Error>,
+    {
+        self.register::<M>(prefix, provider);
+        self
+    }
+
+    /// Registers a Gemini client under the `gemini` prefix.
+    #[cfg(feature = "gemini")]
+    #[must_use]
+    pub fn with_gemini(self, gemini: crate::gemini::Gemini) -> Self {
+        self.with_provider::<crate::gemini::GeminiModel>(Self::GEMINI, Arc::new(gemini))
+    }
+
+    /// Registers an OpenAI client under the `openai` prefix.
+    #[cfg(feature = "openai")]
+    #[must_use]
+    pub fn with_openai(self, openai: crate::openai::Openai) -> Self {
+        self.with_provider::<crate::openai::OpenaiModel>(Self::OPENAI, Arc::new(openai))
+    }
+
+    /// Registers an OpenRouter client under the `openrouter` prefix.
+    #[cfg(feature = "openrouter")]
+    #[must_use]
+    pub fn with_openrouter(self, openrouter: crate::openrouter::Openrouter) -> Self {
+        self.with_provider::<crate::openrouter::OpenrouterModel>(
+            Self::OPENROUTER,
+            Arc::new(openrouter),
+        )
+    }
+
+    /// Returns the provider registered under `prefix`.
+    #[must_use]
+    pub fn provider(&self, prefix: &str) -> Option<Arc<dyn AiProvider>> {
+        self.entries
+            .get(prefix)
+            .map(|entry| Arc::clone(&entry.provider))
+    }
+
+    /// Returns the registered prefixes, in no particular order.
+    pub fn prefixes(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Sets the model used when only the provider prefix is given to [`ModelRegistry::resolve`].
+    ///
+    /// # Errors
+    /// - [`Error::ProviderError`] if no provider is registered under the prefix.
+    /// - [`Error::InvalidModelError`] if the provider rejects the model id.
+    pub fn set_provider_default_model(
+        &mut self,
+        prefix: &str,
+        model_id: impl Into<String>,
+    ) -> Result<()> {
+        let entry = self
+            .entries
+            .get_mut(prefix)
+            .ok_or_else(|| unregistered(prefix))?;
+
+        let model_id = model_id.into();
+        (entry.parse_model)(&model_id)?;
+        entry.default_model = Some(model_id);
+
+        Ok(())
+    }
+
+    /// Sets the provider-qualified model returned by [`ModelRegistry::default_model`].
+    ///
+    /// # Errors
+    /// Returns the same errors as [`ModelRegistry::resolve`].
+    pub fn set_default_model(&mut self, qualified: impl Into<String>) -> Result<()> {
+        let qualified = qualified.into();
+        self.resolve(&qualified)?;
+        self.default_model = Some(qualified);
+
+        Ok(())
+    }
+
+    /// Resolves the default model.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidModelError`] if no default model is set.
+    pub fn default_model(&self) -> Result<(Arc<dyn AiProvider>, Box<dyn AiModel>)> {
+        let qualified = self
+            .default_model
+            .as_deref()
+            .ok_or_else(|| Error::InvalidModelError("No default model is set".to_owned()))?;
+
+        self.resolve(qualified)
+    }
+
+    /// Resolves a provider-qualified model string, such as `openrouter:anthropic/claude-3.5-sonnet`.
+    ///
+    /// The string is split at the first `:`, so model ids may contain colons themselves.
+    /// A bare provider prefix resolves to the default model of that provider, if it has one.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidModelError`] if the string has no provider prefix, or the provider
+    ///   rejects the model id.
+    /// - [`Error::ProviderError`] if no provider is registered under the prefix.
+    pub fn resolve(&self, qualified: &str) -> Result<(Arc<dyn AiProvider>, Box<dyn AiModel>)> {
+        if let Some(entry) = self.entries.get(qualified)
+            && let Some(model_id) = &entry.default_model
+        {
+            let model = (entry.parse_model)(model_id)?;
+            return Ok((Arc::clone(&entry.provider), model));
+        }
+
+        let (prefix, model_id) = split_qualified(qualified)
+            .ok_or_else(|| Error::InvalidModelError(qualified.to_owned()))?;
+
+        let entry = self
+            .entries
+            .get(prefix)
+            .ok_or_else(|| unregistered(prefix))?;
+
+        let model = (entry.parse_model)(model_id)?;
+
+        Ok((Arc::clone(&entry.provider), model))
+    }
+}
+
+/// Splits a provider-qualified model string into its prefix and model id.
+///
+/// Returns `None` if either part is empty or there is no `:` separator.
+#[must_use]
+pub fn split_qualified(qualified: &str) -> Option<(&str, &str)> {
+    qualified
+        .split_once(':')
+        .filter(|(prefix, model_id)| !prefix.is_empty() && !model_id.is_empty())
+}
+
+fn unregistered(prefix: &str) -> Error {
+    Error::ProviderError {
+        provider: prefix.to_owned(),
+        error: "No provider registered for this prefix".to_owned(),
+    }
+}
+
+fn parse_model<M>(model_id: &str) -> Result<Box<dyn AiModel>>
+where
+    M: AiModel + FromStr<Err = Error>,
+{
+    Ok(Box::new(model_id.parse::<M>()?))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use futures::{FutureExt, StreamExt, stream::BoxStream};
+    use latchlm_core::{AiRequest, AiResponse, BoxFuture, ModelId};
+    use std::borrow::Cow;
+
+    struct TestProvider;
+
+    impl AiProvider for TestProvider {
+        fn send_request(
+            &self,
+            model: &dyn AiModel,
+            _request: AiRequest,
+        ) -> BoxFuture<'_, Result<AiResponse>> {
+            let response = AiResponse {
+                text: model.as_ref().to_owned(),
+                ..Default::default()
+            };
+
+            futures::future::ready(Ok(response)).boxed()
+        }
+
+        fn send_streaming(
+            &self,
+            _model: &dyn AiModel,
+            _request: AiRequest,
+        ) -> BoxStream<'_, Result<AiResponse>> {
+            futures::stream::empty().boxed()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct TestModel(String);
+
+    impl AsRef<str> for TestModel {
+        fn as_ref(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl AiModel for TestModel {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn model_id(&self) -> ModelId<'_> {
+            ModelId {
+                id: Cow::Borrowed(&self.0),
+                name: Cow::Borrowed(&self.0),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl FromStr for TestModel {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            if s == "unknown" {
+                return Err(Error::InvalidModelError(s.to_owned()));
+            }
+
+            Ok(Self(s.to_owned()))
+        }
+    }
+
+    fn registry() -> ModelRegistry {
+        ModelRegistry::new().with_provider::<TestModel>("test", Arc::new(TestProvider))
+    }
+
+    #[test]
+    fn test_split_qualified() {
+        assert_eq!(
+            split_qualified("openrouter:meta-llama/llama-3-8b:free"),
+            Some(("openrouter", "meta-llama/llama-3-8b:free"))
+        );
+        assert_eq!(split_qualified("gemini-2.5-flash"), None);
+        assert_eq!(split_qualified(":gemini-2.5-flash"), None);
+        assert_eq!(split_qualified("gemini:"), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let (provider, model) = registry().resolve("test:model-1").unwrap();
+
+        assert_eq!(model.model_id().id, "model-1");
+
+        let response = provider
+            .send_request(&*model, AiRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(response.text, "model-1");
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let registry = registry();
+
+        assert!(matches!(
+            registry.resolve("model-1"),
+            Err(Error::InvalidModelError(_))
+        ));
+        assert!(matches!(
+            registry.resolve("other:model-1"),
+            Err(Error::ProviderError { provider, .. }) if provider == "other"
+        ));
+        assert!(matches!(
+            registry.resolve("test:unknown"),
+            Err(Error::InvalidModelError(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_models() {
+        let mut registry = registry();
+
+        assert!(matches!(
+            registry.default_model(),
+            Err(Error::InvalidModelError(_))
+        ));
+        assert!(registry.resolve("test").is_err());
+
+        registry
+            .set_provider_default_model("test", "model-1")
+            .unwrap();
+        registry.set_default_model("test:model-2").unwrap();
+
+        let (_, model) = registry.resolve("test").unwrap();
+        assert_eq!(model.model_id().id, "model-1");
+
+        let (_, model) = registry.default_model().unwrap();
+        assert_eq!(model.model_id().id, "model-2");
+
+        assert!(
+            registry
+                .set_provider_default_model("test", "unknown")
+                .is_err()
+        );
+        assert!(
+            registry
+                .set_provider_default_model("other", "model-1")
+                .is_err()
+        );
+        assert!(registry.set_default_model("other:model-1").is_err());
+    }
+
+    #[test]
+    fn test_register_replaces_provider() {
+        let mut registry = registry();
+
+        assert!(registry.provider("test").is_some());
+        assert!(
+            registry
+                .register::<TestModel>("test", Arc::new(TestProvider))
+                .is_some()
+        );
+        assert_eq!(registry.prefixes().collect::<Vec<_>>(), ["test"]);
+    }
+}
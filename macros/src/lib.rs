@@ -10,19 +10,39 @@ use syn::{Data, DeriveInput, parse_macro_input};
 ///
 /// This macro can be used to automatically implement the `AiModel` trait for enums representing model variants.
 /// Each enum variant must be annotated with a `#[model(id = "...", name = "...")] attribute specifying the model's
-/// technical identifier and human-readable name.
+/// technical identifier and human-readable name. A tuple variant holding a `String` can instead be
+/// marked with `#[model(other)]` to accept any other model id.
 ///
 /// The macro will implement:
 /// - `AiModel` (with `model_id`)
 /// - `AsRef<str>`
 /// - `TryFrom<&str>`
 /// - `serde::Serialize` and `serde::Deserialize`
+/// - `Default`, if a variant is marked with `default`
 /// - A static `variants()` method returning all model IDs.
 ///
+/// Besides `id` and `name`, a variant accepts:
+/// - `alias = "..."`, repeatable, for other ids that parse to the variant. Models are always
+///   converted back to their canonical `id`.
+/// - `deprecated = "..."`, a note exposed through `ModelId::deprecated`. This is runtime
+///   metadata only: mark the variant `#[deprecated(note = "...")]` instead to also warn where
+///   it is used, and the derive takes the note from that attribute.
+/// - `default`, to make the variant the `Default` of the enum
+///
+/// The attribute can also declare the model's capabilities, all of which are optional:
+/// - `context_window` and `max_output_tokens`, as integer literals
+/// - `input_modalities` and `output_modalities`, as comma separated lists of `text`, `image`,
+///   `audio`, `video` and `document`
+/// - `tool_calling`, `structured_output`, `reasoning` and `streaming`, as boolean literals
+///
 /// # Errors
 /// - Only enums are supported.
 /// - Each variant must have both `id` and `name` specified in the `#[model]` attribute.
-/// - Only `id` and `name` are supported keys in the attribute.
+/// - Only `id`, `name`, `alias`, `deprecated`, `default` and the capability keys are supported
+///   in the attribute.
+/// - Ids and aliases must all be unique, and at most one variant can be the `default`.
+/// - At most one variant can be marked with `other`. It must be a tuple variant holding a
+///   `String` and cannot declare any other key.
 ///
 /// # Example
 /// ```
@@ -31,9 +51,15 @@ use syn::{Data, DeriveInput, parse_macro_input};
 ///
 /// #[derive(AiModel)]
 /// pub enum MyModel {
-///     #[model(id = "mymodel-variant-1", name = "My Model Variant 1")]
+///     #[model(id = "mymodel-variant-1", name = "My Model Variant 1", alias = "mymodel-v1", default)]
 ///     Variant1,
-///     #[model(id = "mymodel-variant-2", name = "My Model Variant 2")]
+///     #[model(
+///         id = "mymodel-variant-2",
+///         name = "My Model Variant 2",
+///         context_window = 128_000,
+///         input_modalities = "text, image",
+///         tool_calling = true,
+///     )]
 ///     Variant2,
 /// }
 /// ```
@@ -57,17 +83,46 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
         ));
     };
 
-    let variant_infos = data_enum
-        .variants
-        .iter()
-        .map(|variant| {
-            let variant_name = &variant.ident;
-            let (id_value, name_value) = extract_model_attributes(variant)?;
-            Ok((variant_name, id_value, name_value))
-        })
-        .collect::<syn::Result<Vec<_>>>()?;
+    let mut variant_infos = Vec::new();
+    let mut other_variant = None;
+
+    for variant in &data_enum.variants {
+        if is_other_variant(variant) {
+            if other_variant.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    "Only one variant can be marked with #[model(other)]",
+                ));
+            }
+
+            let syn::Fields::Unnamed(fields) = &variant.fields else {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "The #[model(other)] variant must be a tuple variant holding a String",
+                ));
+            };
+            let [field] = fields.unnamed.iter().collect::<Vec<_>>()[..] else {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "The #[model(other)] variant must be a tuple variant holding a String",
+                ));
+            };
+            if !is_string(&field.ty) {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "The #[model(other)] variant must hold a String",
+                ));
+            }
 
-    let set: std::collections::HashSet<_> = variant_infos.iter().map(|(_, id, _)| id).collect();
+            other_variant = Some(&variant.ident);
+            continue;
+        }
+
+        variant_infos.push((&variant.ident, extract_model_attributes(variant)?));
+    }
+
+    let set: std::collections::HashSet<_> =
+        variant_infos.iter().map(|(_, attrs)| &attrs.id).collect();
     if set.len() != variant_infos.len() {
         return Err(syn::Error::new_spanned(
             input,
@@ -75,73 +130,171 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
         ));
     }
 
-    let as_ref_arms = variant_infos.iter().map(|(variant_name, id_value, _)| {
+    let mut known_ids = set
+        .into_iter()
+        .cloned()
+        .collect::<std::collections::HashSet<_>>();
+    for alias in variant_infos.iter().flat_map(|(_, attrs)| &attrs.aliases) {
+        if !known_ids.insert(alias.value()) {
+            return Err(syn::Error::new_spanned(
+                alias,
+                "Repeated Id: model alias must be unique and differ from all model ids",
+            ));
+        }
+    }
+
+    let mut default_variants = variant_infos
+        .iter()
+        .filter(|(_, attrs)| attrs.default)
+        .map(|(variant_name, _)| variant_name);
+    let default_variant = default_variants.next();
+    if let Some(variant_name) = default_variants.next() {
+        return Err(syn::Error::new_spanned(
+            variant_name,
+            "Only one variant can be marked with #[model(default)]",
+        ));
+    }
+
+    let as_ref_arms = variant_infos.iter().map(|(variant_name, attrs)| {
+        let id_value = &attrs.id;
         quote! {
             #name::#variant_name => #id_value,
         }
     });
 
-    let try_from_arms = variant_infos
+    // Aliases are accepted when parsing, but models are always converted back to their id.
+    let parse_arms = variant_infos
         .iter()
-        .map(|(variant_name, id_value, _)| {
+        .map(|(variant_name, attrs)| {
+            let id_value = &attrs.id;
+            let aliases = &attrs.aliases;
             quote! {
-                #id_value => Ok(#name::#variant_name),
+                #id_value #(| #aliases)* => Ok(#name::#variant_name),
             }
         })
         .collect::<Vec<_>>();
 
     let serde_serialize_arms = variant_infos
         .iter()
-        .map(|(variant_name, id_value, _)| {
+        .map(|(variant_name, attrs)| {
+            let id_value = &attrs.id;
             quote! {
                 #name::#variant_name => serializer.serialize_str(#id_value),
             }
         })
         .collect::<Vec<_>>();
 
-    let serde_deserialize_arms = variant_infos
-        .iter()
-        .map(|(variant_name, id_value, _)| {
-            quote! {
-                #id_value => Ok(#name::#variant_name),
-            }
-        })
-        .collect::<Vec<_>>();
-
     let valid_variants = variant_infos
         .iter()
-        .map(|(_, id_value, _)| id_value.as_str())
+        .map(|(_, attrs)| attrs.id.as_str())
         .collect::<Vec<_>>();
 
-    let expecting_message = format!("one of: {}", valid_variants.join(", "));
+    let expecting_message = if other_variant.is_some() {
+        "a model id".to_string()
+    } else {
+        format!("one of: {}", valid_variants.join(", "))
+    };
 
-    let model_id_arms = variant_infos
+    let model_ids = variant_infos
         .iter()
-        .map(|(variant_name, id, model_name)| {
+        .map(|(_, attrs)| {
+            let id = &attrs.id;
+            let model_name = &attrs.name;
+            let capabilities = attrs.capabilities.to_tokens();
+            let deprecated = match &attrs.deprecated {
+                Some(note) => quote! {
+                    ::core::option::Option::Some(::std::borrow::Cow::Borrowed(#note))
+                },
+                None => quote! { ::core::option::Option::None },
+            };
+
             quote! {
-                #name::#variant_name => ::latchlm_core::ModelId {
+                ::latchlm_core::ModelId {
                     id: ::std::borrow::Cow::Borrowed(#id),
                     name: ::std::borrow::Cow::Borrowed(#model_name),
+                    capabilities: #capabilities,
+                    deprecated: #deprecated,
                 }
             }
         })
         .collect::<Vec<_>>();
 
-    let array_arms = variant_infos
+    let model_id_arms = variant_infos
         .iter()
-        .map(|(_, id, model_name)| {
+        .zip(&model_ids)
+        .map(|((variant_name, _), model_id)| {
             quote! {
-                ::latchlm_core::ModelId {
-                    id: ::std::borrow::Cow::Borrowed(#id),
-                    name: ::std::borrow::Cow::Borrowed(#model_name),
-                }
+                #name::#variant_name => #model_id
             }
         })
         .collect::<Vec<_>>();
 
-    let array_len = array_arms.len();
+    let array_len = model_ids.len();
+
+    // The catch-all variant holds the id itself, which doubles as its name.
+    let other_as_ref_arm = other_variant.map(|other| {
+        quote! {
+            #name::#other(id) => id.as_str(),
+        }
+    });
+
+    let other_model_id_arm = other_variant.map(|other| {
+        quote! {
+            #name::#other(id) => ::latchlm_core::ModelId {
+                id: ::std::borrow::Cow::Borrowed(id.as_str()),
+                name: ::std::borrow::Cow::Borrowed(id.as_str()),
+                capabilities: ::latchlm_core::ModelCapabilities::new(),
+                deprecated: ::core::option::Option::None,
+            },
+        }
+    });
 
+    let other_serialize_arm = other_variant.map(|other| {
+        quote! {
+            #name::#other(id) => serializer.serialize_str(id),
+        }
+    });
+
+    // Blank ids are rejected even by the catch-all variant, they are never valid models.
+    let try_from_fallback = match other_variant {
+        Some(other) => quote! {
+            blank_model if blank_model.trim().is_empty() => {
+                Err(::latchlm_core::Error::InvalidModelError(blank_model.to_string()))
+            }
+            other_model => Ok(#name::#other(other_model.to_string())),
+        },
+        None => quote! {
+            invalid_model => Err(::latchlm_core::Error::InvalidModelError(invalid_model.to_string())),
+        },
+    };
+
+    let deserialize_fallback = match other_variant {
+        Some(other) => quote! {
+            blank_model if blank_model.trim().is_empty() => Err(::serde::de::Error::invalid_value(
+                ::serde::de::Unexpected::Str(blank_model),
+                &self,
+            )),
+            other_model => Ok(#name::#other(other_model.to_string())),
+        },
+        None => quote! {
+            invalid_model => Err(::serde::de::Error::unknown_variant(value, &[#(#valid_variants),*])),
+        },
+    };
+
+    let default_impl = default_variant.map(|variant_name| {
+        quote! {
+            #[allow(deprecated)]
+            impl ::core::default::Default for #name {
+                fn default() -> Self {
+                    #name::#variant_name
+                }
+            }
+        }
+    });
+
+    // Variants may also carry `#[deprecated]`, so the generated code must not warn about them.
     let expanded = quote! {
+        #[allow(deprecated)]
         impl AiModel for #name {
             fn as_any(&self) -> &dyn ::std::any::Any {
                 self
@@ -149,16 +302,19 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
 
             fn model_id(&self) -> ::latchlm_core::ModelId {
                 match self {
-                    #(#model_id_arms),*
+                    #(#model_id_arms,)*
+                    #other_model_id_arm
                 }
             }
 
         }
 
+        #[allow(deprecated)]
         impl ::core::convert::AsRef<str> for #name {
             fn as_ref(&self) -> &str {
                 match self {
                     #(#as_ref_arms)*
+                    #other_as_ref_arm
                 }
             }
         }
@@ -170,16 +326,18 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             }
         }
 
+        #[allow(deprecated)]
         impl ::core::convert::TryFrom<&str> for #name {
             type Error = ::latchlm_core::Error;
             fn try_from(value: &str) -> ::latchlm_core::Result<Self> {
                 match value {
-                    #(#try_from_arms)*
-                    invalid_model => Err(::latchlm_core::Error::InvalidModelError(invalid_model.to_string())),
+                    #(#parse_arms)*
+                    #try_from_fallback
                 }
             }
         }
 
+        #[allow(deprecated)]
         impl ::serde::Serialize for #name {
             fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
             where
@@ -187,6 +345,7 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             {
                 match self {
                     #(#serde_serialize_arms)*
+                    #other_serialize_arm
                 }
             }
         }
@@ -198,6 +357,7 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             {
                 struct ModelVisitor;
 
+                #[allow(deprecated)]
                 impl<'de> ::serde::de::Visitor<'de> for ModelVisitor {
                     type Value = #name;
 
@@ -210,8 +370,8 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                         E: ::serde::de::Error,
                     {
                         match value {
-                            #(#serde_deserialize_arms)*
-                            invalid_model => Err(::serde::de::Error::unknown_variant(value, &[#(#valid_variants),*]))
+                            #(#parse_arms)*
+                            #deserialize_fallback
                         }
                     }
                 }
@@ -220,10 +380,12 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             }
         }
 
+        #default_impl
+
         impl #name {
             pub fn variants() -> &'static [::latchlm_core::ModelId::<'static>] {
                 const VARS: [::latchlm_core::ModelId::<'static>; #array_len] = [
-                    #(#array_arms),*
+                    #(#model_ids),*
                 ];
 
                 &VARS
@@ -234,11 +396,211 @@ fn ai_model_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
     Ok(expanded.into())
 }
 
-fn extract_model_attributes(variant: &syn::Variant) -> syn::Result<(String, String)> {
-    use syn::{Error, Expr, Lit, Meta};
+/// Returns `true` if the type is written as `String`, possibly with its full path.
+fn is_string(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+
+    type_path.qself.is_none()
+        && type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_none())
+}
+
+/// Returns `true` if the variant is marked as the catch-all with `#[model(other)]`.
+fn is_other_variant(variant: &syn::Variant) -> bool {
+    variant.attrs.iter().any(|attr| {
+        attr.path().is_ident("model")
+            && attr
+                .parse_args::<syn::Path>()
+                .is_ok_and(|path| path.is_ident("other"))
+    })
+}
+
+/// The keys of the `#[model]` attribute of a variant.
+struct ModelAttributes {
+    id: String,
+    name: String,
+    aliases: Vec<syn::LitStr>,
+    deprecated: Option<String>,
+    default: bool,
+    capabilities: Capabilities,
+}
+
+/// The capabilities that can be declared in the `#[model]` attribute.
+#[derive(Default)]
+struct Capabilities {
+    context_window: Option<syn::LitInt>,
+    max_output_tokens: Option<syn::LitInt>,
+    input_modalities: Vec<syn::Ident>,
+    output_modalities: Vec<syn::Ident>,
+    tool_calling: Option<syn::LitBool>,
+    structured_output: Option<syn::LitBool>,
+    reasoning: Option<syn::LitBool>,
+    streaming: Option<syn::LitBool>,
+}
+
+impl Capabilities {
+    /// Generates a constant `ModelCapabilities` expression.
+    fn to_tokens(&self) -> proc_macro2::TokenStream {
+        fn option<T: quote::ToTokens>(value: Option<&T>) -> proc_macro2::TokenStream {
+            match value {
+                Some(value) => quote! { ::core::option::Option::Some(#value) },
+                None => quote! { ::core::option::Option::None },
+            }
+        }
+
+        let context_window = option(self.context_window.as_ref());
+        let max_output_tokens = option(self.max_output_tokens.as_ref());
+        let input_modalities = &self.input_modalities;
+        let output_modalities = &self.output_modalities;
+        let tool_calling = option(self.tool_calling.as_ref());
+        let structured_output = option(self.structured_output.as_ref());
+        let reasoning = option(self.reasoning.as_ref());
+        let streaming = option(self.streaming.as_ref());
+
+        quote! {
+            ::latchlm_core::ModelCapabilities {
+                context_window: #context_window,
+                max_output_tokens: #max_output_tokens,
+                input_modalities: ::std::borrow::Cow::Borrowed(&[
+                    #(::latchlm_core::Modality::#input_modalities),*
+                ]),
+                output_modalities: ::std::borrow::Cow::Borrowed(&[
+                    #(::latchlm_core::Modality::#output_modalities),*
+                ]),
+                tool_calling: #tool_calling,
+                structured_output: #structured_output,
+                reasoning: #reasoning,
+                streaming: #streaming,
+            }
+        }
+    }
+}
+
+fn parse_int(value: &syn::Expr, key: &str) -> syn::Result<syn::LitInt> {
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit_int),
+            ..
+        }) => Ok(lit_int.clone()),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            format!("Model {key} must be an integer literal"),
+        )),
+    }
+}
+
+fn parse_bool(value: &syn::Expr, key: &str) -> syn::Result<syn::LitBool> {
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Bool(lit_bool),
+            ..
+        }) => Ok(lit_bool.clone()),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            format!("Model {key} must be `true` or `false`"),
+        )),
+    }
+}
+
+fn parse_modalities(value: &syn::Expr, key: &str) -> syn::Result<Vec<syn::Ident>> {
+    let error = || {
+        syn::Error::new_spanned(
+            value,
+            format!(
+                "Model {key} must be a comma separated list of modalities, e.g. \"text, image\""
+            ),
+        )
+    };
+
+    let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(lit_str),
+        ..
+    }) = value
+    else {
+        return Err(error());
+    };
+
+    lit_str
+        .value()
+        .split(',')
+        .map(str::trim)
+        .filter(|modality| !modality.is_empty())
+        .map(|modality| {
+            let variant = match modality {
+                "text" => "Text",
+                "image" => "Image",
+                "audio" => "Audio",
+                "video" => "Video",
+                "document" => "Document",
+                _ => return Err(error()),
+            };
+
+            Ok(syn::Ident::new(variant, lit_str.span()))
+        })
+        .collect()
+}
+
+fn parse_str(value: &syn::Expr, key: &str) -> syn::Result<syn::LitStr> {
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit_str),
+            ..
+        }) => Ok(lit_str.clone()),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            format!("Model {key} must be a string literal"),
+        )),
+    }
+}
+
+/// Returns the note of a `#[deprecated]` attribute, if the variant carries one.
+///
+/// A `#[deprecated]` attribute without a note yields an empty note.
+fn deprecation_note(variant: &syn::Variant) -> syn::Result<Option<syn::LitStr>> {
+    let Some(attr) = variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("deprecated"))
+    else {
+        return Ok(None);
+    };
+
+    let mut note = None;
+    match &attr.meta {
+        syn::Meta::Path(_) => {}
+        syn::Meta::NameValue(name_value) => note = Some(parse_str(&name_value.value, "note")?),
+        syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+            let value = meta.value()?.parse::<syn::LitStr>()?;
+            if meta.path.is_ident("note") {
+                note = Some(value);
+            }
+            Ok(())
+        })?,
+    }
+
+    Ok(Some(note.unwrap_or_else(|| {
+        syn::LitStr::new("", proc_macro2::Span::call_site())
+    })))
+}
+
+const UNSUPPORTED_KEY: &str = "Unsupported key in #[model] attribute, expected one of: id, name, \
+    alias, deprecated, default, context_window, max_output_tokens, input_modalities, \
+    output_modalities, tool_calling, structured_output, reasoning, streaming";
+
+fn extract_model_attributes(variant: &syn::Variant) -> syn::Result<ModelAttributes> {
+    use syn::{Error, Meta};
 
     let mut model_id = None;
     let mut model_name = None;
+    let mut aliases = Vec::new();
+    let mut deprecated = None;
+    let mut default = false;
+    let mut capabilities = Capabilities::default();
 
     for attr in &variant.attrs {
         if !attr.path().is_ident("model") {
@@ -251,54 +613,47 @@ fn extract_model_attributes(variant: &syn::Variant) -> syn::Result<(String, Stri
 
         for meta in args {
             match meta {
-                Meta::NameValue(name_value) if name_value.path.is_ident("id") => {
-                    match &name_value.value {
-                        Expr::Lit(expr_lit) => match &expr_lit.lit {
-                            Lit::Str(lit_str) => {
-                                model_id = Some(lit_str.value());
-                            }
-                            _ => {
-                                return Err(Error::new_spanned(
-                                    &name_value.value,
-                                    "Model id must be a string literal",
-                                ));
-                            }
-                        },
-                        _ => {
-                            return Err(Error::new_spanned(
-                                &name_value.value,
-                                "Model id must be a string literal",
-                            ));
-                        }
-                    }
+                Meta::Path(path) if path.is_ident("default") => default = true,
+                Meta::Path(path) if path.is_ident("other") => {
+                    return Err(Error::new_spanned(
+                        path,
+                        "#[model(other)] cannot be combined with other keys",
+                    ));
                 }
-                Meta::NameValue(name_value) if name_value.path.is_ident("name") => {
-                    match &name_value.value {
-                        Expr::Lit(expr_lit) => match &expr_lit.lit {
-                            Lit::Str(lit_str) => {
-                                model_name = Some(lit_str.value());
-                            }
-                            _ => {
-                                return Err(Error::new_spanned(
-                                    &name_value.value,
-                                    "Model name must be a string literal",
-                                ));
-                            }
-                        },
-                        _ => {
-                            return Err(Error::new_spanned(
-                                &name_value.value,
-                                "Model name must be a string literal",
-                            ));
+                Meta::NameValue(name_value) => {
+                    let value = &name_value.value;
+                    let Some(key) = name_value.path.get_ident().map(ToString::to_string) else {
+                        return Err(Error::new_spanned(&name_value.path, UNSUPPORTED_KEY));
+                    };
+
+                    match key.as_str() {
+                        "id" => model_id = Some(parse_str(value, &key)?.value()),
+                        "name" => model_name = Some(parse_str(value, &key)?.value()),
+                        "alias" => aliases.push(parse_str(value, &key)?),
+                        "deprecated" => deprecated = Some(parse_str(value, &key)?),
+                        "context_window" => {
+                            capabilities.context_window = Some(parse_int(value, &key)?);
+                        }
+                        "max_output_tokens" => {
+                            capabilities.max_output_tokens = Some(parse_int(value, &key)?);
+                        }
+                        "input_modalities" => {
+                            capabilities.input_modalities = parse_modalities(value, &key)?;
+                        }
+                        "output_modalities" => {
+                            capabilities.output_modalities = parse_modalities(value, &key)?;
+                        }
+                        "tool_calling" => {
+                            capabilities.tool_calling = Some(parse_bool(value, &key)?);
+                        }
+                        "structured_output" => {
+                            capabilities.structured_output = Some(parse_bool(value, &key)?);
                         }
+                        "reasoning" => capabilities.reasoning = Some(parse_bool(value, &key)?),
+                        "streaming" => capabilities.streaming = Some(parse_bool(value, &key)?),
+                        _ => return Err(Error::new_spanned(&name_value.path, UNSUPPORTED_KEY)),
                     }
                 }
-                Meta::NameValue(name_value) => {
-                    return Err(Error::new_spanned(
-                        &name_value.path,
-                        "Only 'id' and 'name' are supported in #[model] attribute",
-                    ));
-                }
                 _ => {
                     return Err(Error::new_spanned(
                         meta,
@@ -316,5 +671,24 @@ fn extract_model_attributes(variant: &syn::Variant) -> syn::Result<(String, Stri
         Error::new_spanned(&variant.ident, "missing #[model] attribute with name")
     })?;
 
-    Ok((id, name))
+    // A note in `#[deprecated]` is used unless the attribute repeats it.
+    let deprecated = match (deprecated, deprecation_note(variant)?) {
+        (Some(key), Some(note)) if !note.value().is_empty() => {
+            return Err(Error::new_spanned(
+                key,
+                "The deprecation note is already given by #[deprecated]",
+            ));
+        }
+        (Some(key), _) => Some(key.value()),
+        (None, note) => note.map(|note| note.value()),
+    };
+
+    Ok(ModelAttributes {
+        id,
+        name,
+        aliases,
+        deprecated,
+        default,
+        capabilities,
+    })
 }
@@ -0,0 +1,12 @@
+#![allow(unused)]
+
+use latchlm_core::AiModel;
+use latchlm_macros::AiModel;
+
+#[derive(AiModel)]
+enum Model {
+    #[model(id = "variant", name = "Variant", input_modalities = "text, smell")]
+    Variant,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#![allow(unused)]
+
+use latchlm_core::AiModel;
+use latchlm_macros::AiModel;
+
+#[derive(AiModel)]
+enum Model {
+    #[model(id = "variant-1", name = "V1", default)]
+    Variant1,
+    #[model(id = "variant-2", name = "V2", default)]
+    Variant2,
+}
+
+fn main() {}
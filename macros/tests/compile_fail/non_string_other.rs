@@ -0,0 +1,14 @@
+#![allow(unused)]
+
+use latchlm_core::AiModel;
+use latchlm_macros::AiModel;
+
+#[derive(AiModel)]
+enum Model {
+    #[model(id = "variant", name = "Variant")]
+    Variant,
+    #[model(other)]
+    Custom(u32),
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![allow(unused)]
+
+use latchlm_core::AiModel;
+use latchlm_macros::AiModel;
+
+#[derive(AiModel)]
+enum Model {
+    #[deprecated(note = "Use another variant")]
+    #[model(id = "variant", name = "Variant", deprecated = "Use another variant")]
+    Variant,
+}
+
+fn main() {}
@@ -0,0 +1,42 @@
+#![allow(unused)]
+
+use latchlm_core::AiModel;
+use latchlm_macros::AiModel;
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+use serde::de::value::{Error, StrDeserializer};
+
+#[derive(Debug, PartialEq, AiModel)]
+enum Model {
+    #[model(id = "variant-1", name = "V1", alias = "variant-1-001", alias = "v1")]
+    Variant1,
+    #[model(id = "variant-2", name = "V2", default)]
+    Variant2,
+    #[deprecated]
+    #[model(id = "variant-3", name = "V3", deprecated = "Use variant-2 instead")]
+    Variant3,
+    #[deprecated(since = "0.2.0", note = "Use variant-1 instead")]
+    #[model(id = "variant-4", name = "V4")]
+    Variant4,
+}
+
+fn main() {
+    assert_eq!(Model::try_from("variant-1-001").unwrap(), Model::Variant1);
+    assert_eq!("v1".parse::<Model>().unwrap(), Model::Variant1);
+    assert_eq!(Model::try_from("v1").unwrap().as_ref(), "variant-1");
+
+    let deserializer: StrDeserializer<'_, Error> = "variant-1-001".into_deserializer();
+    assert_eq!(Model::deserialize(deserializer).unwrap(), Model::Variant1);
+
+    assert_eq!(Model::default(), Model::Variant2);
+
+    assert_eq!(Model::Variant1.model_id().deprecated, None);
+    assert_eq!(
+        Model::variants()[2].deprecated.as_deref(),
+        Some("Use variant-2 instead")
+    );
+    assert_eq!(
+        Model::variants()[3].deprecated.as_deref(),
+        Some("Use variant-1 instead")
+    );
+}
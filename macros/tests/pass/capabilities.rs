@@ -0,0 +1,31 @@
+#![allow(unused)]
+
+use latchlm_core::{AiModel, Modality};
+use latchlm_macros::AiModel;
+
+#[derive(AiModel)]
+enum Model {
+    #[model(
+        id = "variant-1",
+        name = "V1",
+        context_window = 128_000,
+        max_output_tokens = 8_192,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        reasoning = false,
+    )]
+    Variant1,
+    #[model(id = "variant-2", name = "V2")]
+    Variant2,
+}
+
+fn main() {
+    let capabilities = Model::Variant1.capabilities();
+    assert_eq!(capabilities.context_window, Some(128_000));
+    assert!(capabilities.accepts(Modality::Image));
+    assert_eq!(capabilities.reasoning, Some(false));
+    assert_eq!(capabilities.streaming, None);
+
+    assert_eq!(Model::variants()[1].capabilities, Default::default());
+}
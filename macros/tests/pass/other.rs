@@ -0,0 +1,35 @@
+#![allow(unused)]
+
+use latchlm_core::AiModel;
+use latchlm_macros::AiModel;
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+use serde::de::value::{Error, StrDeserializer};
+
+#[derive(Debug, PartialEq, AiModel)]
+enum Model {
+    #[model(id = "variant-1", name = "V1")]
+    Variant1,
+    #[model(other)]
+    Custom(String),
+}
+
+fn main() {
+    assert_eq!("variant-1".parse::<Model>().unwrap(), Model::Variant1);
+
+    let model = Model::try_from("unknown-model").unwrap();
+    assert_eq!(model, Model::Custom("unknown-model".to_owned()));
+    assert_eq!(model.as_ref(), "unknown-model");
+    assert_eq!(model.model_id().id, "unknown-model");
+    assert_eq!(model.model_id().name, "unknown-model");
+
+    let deserializer: StrDeserializer<'_, Error> = "unknown-model".into_deserializer();
+    assert_eq!(Model::deserialize(deserializer).unwrap(), model);
+
+    assert!(Model::try_from("").is_err());
+    assert!(" \t".parse::<Model>().is_err());
+    let deserializer: StrDeserializer<'_, Error> = " ".into_deserializer();
+    assert!(Model::deserialize(deserializer).is_err());
+
+    assert_eq!(Model::variants().len(), 1);
+}
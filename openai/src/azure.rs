@@ -0,0 +1,359 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! A client for the Responses API of Azure OpenAI.
+//!
+//! Azure OpenAI serves models through deployments of a resource, authenticates with an
+//! `api-key` header or a Microsoft Entra ID bearer token, and versions its API with an
+//! `api-version` query parameter. Responses are parsed into the same [`OpenaiResponse`]
+//! and [`OpenaiStreamResponse`] types as the [`Openai`] client.
+//!
+//! [`Openai`]: crate::Openai
+
+use std::{collections::HashMap, future::ready, sync::Arc};
+
+use futures::{FutureExt, StreamExt, stream::BoxStream};
+use latchlm_core::{
+    AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, CredentialProvider, Error, Result,
+    StaticCredential, join_url, parse_event_stream, parse_json, send_authenticated,
+};
+use reqwest::header::HeaderValue;
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::{
+    OpenaiError, OpenaiModel, OpenaiResponse, OpenaiStreamResponse, request::OpenaiRequest,
+};
+
+/// The credentials used to authenticate with Azure OpenAI.
+///
+/// The provider is consulted before every request, and asked for a fresh credential when
+/// the API rejects one with `401 Unauthorized`.
+#[derive(Debug, Clone)]
+pub enum AzureAuth {
+    /// A resource key, sent in the `api-key` header
+    ApiKey(Arc<dyn CredentialProvider>),
+    /// A Microsoft Entra ID access token, sent as a bearer token
+    EntraToken(Arc<dyn CredentialProvider>),
+}
+
+impl AzureAuth {
+    fn credentials(&self) -> &dyn CredentialProvider {
+        match self {
+            Self::ApiKey(credentials) | Self::EntraToken(credentials) => &**credentials,
+        }
+    }
+
+    fn authenticate(
+        &self,
+        request: reqwest::RequestBuilder,
+        credential: &SecretString,
+    ) -> reqwest::RequestBuilder {
+        match self {
+            Self::ApiKey(_) => {
+                request.header(AzureOpenai::API_KEY_HEADER, credential.expose_secret())
+            }
+            Self::EntraToken(_) => request.bearer_auth(credential.expose_secret()),
+        }
+    }
+}
+
+/// A builder for creating an [`AzureOpenai`] client.
+#[derive(Debug, Clone, Default)]
+pub struct AzureOpenaiBuilder {
+    client: Option<reqwest::Client>,
+    endpoint: Option<reqwest::Url>,
+    auth: Option<AzureAuth>,
+    // A fixed credential, checked to be a valid header value when building the client
+    static_credential: Option<SecretString>,
+    api_version: Option<String>,
+    deployments: HashMap<String, String>,
+}
+
+impl AzureOpenaiBuilder {
+    /// Create a new Azure OpenAI builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the HTTP client to use for making requests.
+    #[must_use]
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the endpoint of the Azure OpenAI resource, such as
+    /// `https://my-resource.openai.azure.com`.
+    ///
+    /// Any path prefix is kept, so requests can be routed through API gateways.
+    #[must_use]
+    pub fn endpoint(mut self, endpoint: reqwest::Url) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Authenticate with a resource key.
+    #[must_use]
+    pub fn api_key(mut self, api_key: SecretString) -> Self {
+        self.auth = Some(AzureAuth::ApiKey(Arc::new(StaticCredential::new(
+            api_key.clone(),
+        ))));
+        self.static_credential = Some(api_key);
+        self
+    }
+
+    /// Authenticate with a resource key from a provider consulted before every request.
+    ///
+    /// Use this instead of [`AzureOpenaiBuilder::api_key`] to rotate keys without rebuilding
+    /// the client.
+    #[must_use]
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.auth = Some(AzureAuth::ApiKey(Arc::new(credentials)));
+        self.static_credential = None;
+        self
+    }
+
+    /// Authenticate with a fixed Microsoft Entra ID access token.
+    ///
+    /// Entra ID tokens expire, usually after an hour, so prefer
+    /// [`AzureOpenaiBuilder::entra_credentials`] for long-lived clients.
+    #[must_use]
+    pub fn entra_token(mut self, token: SecretString) -> Self {
+        self.auth = Some(AzureAuth::EntraToken(Arc::new(StaticCredential::new(
+            token.clone(),
+        ))));
+        self.static_credential = Some(token);
+        self
+    }
+
+    /// Authenticate with Microsoft Entra ID access tokens from a provider consulted before
+    /// every request, such as a [`CallbackCredential`] with a time to live shorter than the
+    /// lifetime of the tokens.
+    ///
+    /// [`CallbackCredential`]: latchlm_core::CallbackCredential
+    #[must_use]
+    pub fn entra_credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.auth = Some(AzureAuth::EntraToken(Arc::new(credentials)));
+        self.static_credential = None;
+        self
+    }
+
+    /// Loads the resource key from the `AZURE_OPENAI_API_KEY` environment variable.
+    pub fn api_key_from_env(self) -> std::result::Result<Self, std::env::VarError> {
+        let api_key = std::env::var("AZURE_OPENAI_API_KEY")?;
+
+        Ok(self.api_key(SecretString::from(api_key)))
+    }
+
+    /// Set the `api-version` query parameter sent with every request.
+    ///
+    /// Defaults to [`AzureOpenai::DEFAULT_API_VERSION`].
+    #[must_use]
+    pub fn api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = Some(api_version.into());
+        self
+    }
+
+    /// Serve `model` through the deployment named `deployment`.
+    ///
+    /// Models without a deployment are requested with a deployment named after their id.
+    #[must_use]
+    pub fn deployment(mut self, model: &OpenaiModel, deployment: impl Into<String>) -> Self {
+        self.deployments
+            .insert(model.as_ref().to_owned(), deployment.into());
+        self
+    }
+
+    /// Build the Azure OpenAI client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client, endpoint or credentials are missing, the endpoint
+    /// cannot be a base URL, or a fixed key or token is not a valid HTTP header value, such
+    /// as one with a trailing newline.
+    pub fn build(self) -> Result<AzureOpenai> {
+        let client = self.client.ok_or(OpenaiError::MissingClientError)?;
+        let endpoint = self.endpoint.ok_or(OpenaiError::MissingEndpointError)?;
+        let auth = self.auth.ok_or(OpenaiError::MissingApiKeyError)?;
+
+        if endpoint.cannot_be_a_base() {
+            return Err(Error::InvalidUrlError(endpoint.to_string()));
+        }
+
+        if let Some(credential) = &self.static_credential
+            && HeaderValue::from_str(credential.expose_secret()).is_err()
+        {
+            return Err(Error::CredentialError(
+                "The Azure OpenAI credential is not a valid HTTP header value".into(),
+            ));
+        }
+
+        Ok(AzureOpenai {
+            client,
+            endpoint,
+            auth,
+            api_version: self
+                .api_version
+                .unwrap_or_else(|| AzureOpenai::DEFAULT_API_VERSION.to_owned()),
+            deployments: Arc::new(self.deployments),
+        })
+    }
+}
+
+/// A client for interacting with the Azure OpenAI Responses API.
+#[derive(Debug, Clone)]
+pub struct AzureOpenai {
+    client: reqwest::Client,
+    endpoint: reqwest::Url,
+    auth: AzureAuth,
+    api_version: String,
+    deployments: Arc<HashMap<String, String>>,
+}
+
+impl AzureOpenai {
+    /// The `api-version` used when none is set.
+    pub const DEFAULT_API_VERSION: &str = "2025-04-01-preview";
+
+    // The HTTP header used for key authentication with Azure OpenAI
+    const API_KEY_HEADER: &str = "api-key";
+
+    /// Create a new [`AzureOpenaiBuilder`] instance.
+    #[must_use]
+    pub fn builder() -> AzureOpenaiBuilder {
+        AzureOpenaiBuilder::new()
+    }
+
+    /// Returns the name of the deployment serving `model`.
+    #[must_use]
+    pub fn deployment<'a>(&'a self, model: &'a OpenaiModel) -> &'a str {
+        self.deployments
+            .get(model.as_ref())
+            .map_or(model.as_ref(), String::as_str)
+    }
+
+    fn url(&self) -> Result<reqwest::Url> {
+        let mut url = join_url(&self.endpoint, "openai/responses")?;
+        url.query_pairs_mut()
+            .append_pair("api-version", &self.api_version);
+
+        Ok(url)
+    }
+
+    /// Sends a request to the Azure OpenAI Responses API.
+    ///
+    /// The Responses API takes the deployment name as the model of the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if:
+    /// - The HTTP request fails (network issues, timeout, etc.)
+    /// - The API returns a non-success status code
+    /// - The response body cannot be parsed as valid JSON
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn request(&self, model: OpenaiModel, request: AiRequest) -> Result<OpenaiResponse> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
+        }
+
+        let request = OpenaiRequest::new(self.deployment(&model), request, false);
+
+        let url = self.url()?;
+
+        let response = send_authenticated(self.auth.credentials(), |credential| {
+            self.auth
+                .authenticate(self.client.post(url.clone()), credential)
+                .json(&request)
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Sends a streaming request to the Azure OpenAI Responses API and returns a stream of
+    /// responses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails or the API returns a non-success
+    /// status code. The stream yields an [`Error::ProviderError`] for `error` and
+    /// `response.failed` events.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn streaming_request(
+        &self,
+        model: OpenaiModel,
+        request: AiRequest,
+    ) -> Result<BoxStream<'_, Result<OpenaiStreamResponse>>> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
+        }
+
+        let request = OpenaiRequest::new(self.deployment(&model), request, true);
+
+        let url = self.url()?;
+
+        let response = send_authenticated(self.auth.credentials(), |credential| {
+            self.auth
+                .authenticate(self.client.post(url.clone()), credential)
+                .json(&request)
+        })
+        .await?;
+
+        let stream = parse_event_stream(response, "OpenAI").await?;
+
+        Ok(stream
+            .map(|event| event.and_then(OpenaiStreamResponse::error_for_event))
+            .boxed())
+    }
+}
+
+impl AiProvider for AzureOpenai {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, model)))]
+    fn send_request(
+        &self,
+        model: &dyn AiModel,
+        request: AiRequest,
+    ) -> BoxFuture<'_, Result<AiResponse>> {
+        let Some(model) = model.downcast::<OpenaiModel>() else {
+            let model_name = model.as_ref();
+
+            #[cfg(feature = "tracing")]
+            tracing::error!("Invalid model type: {}", model_name);
+
+            return Box::pin(ready(Err(Error::InvalidModelError(model_name.into()))));
+        };
+
+        Box::pin(async move { self.request(model, request).await.map(Into::into) })
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, model)))]
+    fn send_streaming(
+        &self,
+        model: &dyn AiModel,
+        request: AiRequest,
+    ) -> BoxStream<'_, Result<AiResponse>> {
+        let Some(model) = model.downcast::<OpenaiModel>() else {
+            let model_name = model.as_ref().to_string();
+
+            #[cfg(feature = "tracing")]
+            tracing::error!("Invalid model type: {}", model_name);
+
+            return Box::pin(futures::stream::once(async {
+                Err(Error::InvalidModelError(model_name))
+            }));
+        };
+
+        Box::pin(
+            async move {
+                match self.streaming_request(model, request).await {
+                    Ok(stream) => stream.map(|res| res.map(Into::into)).boxed(),
+                    Err(err) => futures::stream::once(async move { Err(err) }).boxed(),
+                }
+            }
+            .flatten_stream(),
+        )
+    }
+}
@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Background responses, for runs that outlast a synchronous HTTP request.
+//!
+//! A background request returns as soon as the response is queued. The response is then
+//! polled with [`Openai::retrieve_response`] until it leaves the `queued` and `in_progress`
+//! states, and can be cancelled with [`Openai::cancel_response`] in the meantime.
+
+use std::time::Instant;
+
+use latchlm_core::{AiRequest, Error, Result};
+
+pub use latchlm_core::PollOptions;
+
+use crate::{Openai, OpenaiModel, OpenaiRequestOptions, OpenaiResponse, ResponseStatus};
+
+impl Openai {
+    /// Submits a request to run in the background and returns the queued response.
+    ///
+    /// Background responses are always stored, so `options.store` is overridden.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Openai::request`].
+    pub async fn submit_background(
+        &self,
+        model: OpenaiModel,
+        request: AiRequest,
+        options: OpenaiRequestOptions,
+    ) -> Result<OpenaiResponse> {
+        let options = OpenaiRequestOptions {
+            background: Some(true),
+            store: Some(true),
+            ..options
+        };
+
+        self.request_with_options(model, request, options).await
+    }
+
+    /// Polls a stored response until it is no longer queued or in progress, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if retrieving the response fails, the response fails, or the
+    /// timeout of `poll` elapses first. A timed out response keeps running and can still be
+    /// cancelled.
+    pub async fn wait_for_response(
+        &self,
+        response_id: &str,
+        poll: PollOptions,
+    ) -> Result<OpenaiResponse> {
+        let deadline = poll.timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let response = self.retrieve_response(response_id).await?;
+
+            let status = response.status();
+            if !status.is_pending() {
+                return error_for_failure(response);
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!("Response {} is {:?}", response_id, status);
+
+            if deadline.is_some_and(|deadline| Instant::now() + poll.interval > deadline) {
+                return Err(Error::ProviderError {
+                    provider: "OpenAI".into(),
+                    error: format!("Timed out waiting for response {response_id}"),
+                });
+            }
+
+            tokio::time::sleep(poll.interval).await;
+        }
+    }
+
+    /// Runs a request in the background and waits for its final response.
+    ///
+    /// This avoids the HTTP timeouts of long synchronous requests, such as those of
+    /// [`OpenaiModel::Gpto3Pro`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Openai::submit_background`] and [`Openai::wait_for_response`].
+    pub async fn request_in_background(
+        &self,
+        model: OpenaiModel,
+        request: AiRequest,
+        options: OpenaiRequestOptions,
+        poll: PollOptions,
+    ) -> Result<OpenaiResponse> {
+        let response = self.submit_background(model, request, options).await?;
+
+        if !response.status().is_pending() {
+            return error_for_failure(response);
+        }
+
+        self.wait_for_response(response.id(), poll).await
+    }
+}
+
+/// Returns a response that is no longer pending, or an error if it failed.
+fn error_for_failure(response: OpenaiResponse) -> Result<OpenaiResponse> {
+    if response.status() != ResponseStatus::Failed {
+        return Ok(response);
+    }
+
+    Err(Error::ProviderError {
+        provider: "OpenAI".into(),
+        error: response
+            .error_message()
+            .unwrap_or_else(|| format!("Response {} failed", response.id())),
+    })
+}
@@ -0,0 +1,558 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! The Files and Batch APIs, for large offline jobs billed at half the price of synchronous
+//! requests.
+//!
+//! A batch reads its requests from an uploaded JSONL file, built with [`batch_jsonl`], and
+//! runs them against `/v1/responses` within 24 hours. Once the batch is no longer pending,
+//! [`Openai::batch_results`] downloads its output and error files and parses them back into
+//! an [`OpenaiResponse`] per `custom_id`.
+
+use std::{collections::BTreeMap, fmt::Write, time::Instant};
+
+use latchlm_core::{
+    AiRequest, Error, PollOptions, Result, error_for_status, join_url, join_url_segments,
+    parse_json, send_authenticated,
+};
+use reqwest::multipart::{Form, Part};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{Openai, OpenaiModel, OpenaiResponse, request::OpenaiRequest};
+
+/// The endpoint the requests of a batch are sent to.
+const BATCH_ENDPOINT: &str = "/v1/responses";
+
+/// The intended use of an uploaded file.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum FilePurpose {
+    /// The input of a batch
+    Batch,
+    /// The output or errors of a batch, created by the API
+    BatchOutput,
+    /// A file used as a model input
+    UserData,
+    Assistants,
+    Vision,
+    Evals,
+    /// A purpose that is not known to this crate
+    #[serde(other)]
+    Other,
+}
+
+impl FilePurpose {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Batch => "batch",
+            Self::BatchOutput => "batch_output",
+            Self::UserData => "user_data",
+            Self::Assistants => "assistants",
+            Self::Vision => "vision",
+            Self::Evals => "evals",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// A file uploaded with the Files API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OpenaiFile {
+    pub id: String,
+    /// The size of the file in bytes
+    pub bytes: u64,
+    /// The creation time, as a Unix timestamp
+    pub created_at: u64,
+    /// The expiration time, as a Unix timestamp, if the file expires
+    pub expires_at: Option<u64>,
+    pub filename: String,
+    pub purpose: FilePurpose,
+}
+
+/// The lifecycle status of a batch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BatchStatus {
+    /// The input file is being validated
+    Validating,
+    /// The input file failed validation
+    Failed,
+    /// The requests are being run
+    InProgress,
+    /// The output files are being prepared
+    Finalizing,
+    /// Every request was run and the output files are ready
+    Completed,
+    /// The batch did not finish within its completion window
+    Expired,
+    /// The batch is being cancelled
+    Cancelling,
+    /// The batch was cancelled
+    Cancelled,
+    /// A status this crate does not know about
+    Other(String),
+}
+
+impl BatchStatus {
+    /// Returns whether the batch has yet to reach a final status.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            Self::Validating | Self::InProgress | Self::Finalizing | Self::Cancelling
+        )
+    }
+}
+
+impl From<&str> for BatchStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "validating" => Self::Validating,
+            "failed" => Self::Failed,
+            "in_progress" => Self::InProgress,
+            "finalizing" => Self::Finalizing,
+            "completed" => Self::Completed,
+            "expired" => Self::Expired,
+            "cancelling" => Self::Cancelling,
+            "cancelled" => Self::Cancelled,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+/// The number of requests of a batch, by outcome.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchRequestCounts {
+    pub total: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// A batch created with the Batch API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OpenaiBatch {
+    pub id: String,
+    status: String,
+    /// The endpoint the requests are sent to
+    pub endpoint: String,
+    pub input_file_id: String,
+    /// The file holding the successful responses, once the batch is no longer pending
+    pub output_file_id: Option<String>,
+    /// The file holding the failed requests, if any failed
+    pub error_file_id: Option<String>,
+    /// The creation time, as a Unix timestamp
+    pub created_at: u64,
+    /// The completion time, as a Unix timestamp
+    pub completed_at: Option<u64>,
+    /// The time the batch expires, as a Unix timestamp
+    pub expires_at: Option<u64>,
+    pub request_counts: Option<BatchRequestCounts>,
+    /// The validation errors of the input file
+    pub errors: Option<serde_json::Value>,
+    #[serde(default)]
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+impl OpenaiBatch {
+    /// Returns the status of the batch.
+    #[must_use]
+    pub fn status(&self) -> BatchStatus {
+        self.status.as_str().into()
+    }
+
+    /// Returns the messages of the validation errors of the input file, if any.
+    fn error_messages(&self) -> Option<String> {
+        let errors = self.errors.as_ref()?.get("data")?.as_array()?;
+
+        let messages = errors
+            .iter()
+            .filter_map(|error| error.get("message")?.as_str())
+            .collect::<Vec<_>>();
+
+        (!messages.is_empty()).then(|| messages.join("; "))
+    }
+}
+
+/// A line of the input file of a batch.
+#[derive(Serialize, Debug)]
+struct BatchInputLine<'a> {
+    custom_id: String,
+    method: &'static str,
+    url: &'static str,
+    body: &'a OpenaiRequest,
+}
+
+/// A line of the output or error file of a batch.
+#[derive(Deserialize, Debug)]
+struct BatchOutputLine {
+    custom_id: String,
+    response: Option<BatchOutputResponse>,
+    error: Option<BatchOutputError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BatchOutputResponse {
+    status_code: u16,
+    body: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+struct BatchOutputError {
+    message: String,
+}
+
+impl BatchOutputLine {
+    fn into_result(self) -> (String, Result<OpenaiResponse>) {
+        let result = match (self.response, self.error) {
+            (_, Some(error)) => Err(Error::ProviderError {
+                provider: "OpenAI".into(),
+                error: error.message,
+            }),
+            (Some(response), None) if (200..300).contains(&response.status_code) => {
+                serde_json::from_value(response.body).map_err(Into::into)
+            }
+            (Some(response), None) => Err(Error::ApiError {
+                status: response.status_code,
+                message: response.body.to_string(),
+            }),
+            (None, None) => Err(Error::ProviderError {
+                provider: "OpenAI".into(),
+                error: format!("Missing response for request {}", self.custom_id),
+            }),
+        };
+
+        (self.custom_id, result)
+    }
+}
+
+/// Builds the JSONL input file of a batch sending every request to `/v1/responses`.
+///
+/// The `custom_id` of each request is `request-{index}`, `index` being its position in
+/// `requests`, and identifies its result in [`Openai::batch_results`].
+///
+/// # Errors
+///
+/// Returns an [`Error`] if a request cannot be serialized.
+pub fn batch_jsonl<I>(requests: I) -> Result<String>
+where
+    I: IntoIterator<Item = (OpenaiModel, AiRequest)>,
+{
+    let mut jsonl = String::new();
+
+    for (index, (model, request)) in requests.into_iter().enumerate() {
+        let line = BatchInputLine {
+            custom_id: format!("request-{index}"),
+            method: "POST",
+            url: BATCH_ENDPOINT,
+            body: &OpenaiRequest::new(model, request, false),
+        };
+
+        // Writing to a `String` cannot fail
+        let _ = writeln!(jsonl, "{}", serde_json::to_string(&line)?);
+    }
+
+    Ok(jsonl)
+}
+
+impl Openai {
+    /// Uploads a file, such as the JSONL input of a batch, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, such as for a malformed batch input, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, content)))]
+    pub async fn upload_file(
+        &self,
+        filename: &str,
+        content: impl Into<Vec<u8>>,
+        purpose: FilePurpose,
+    ) -> Result<OpenaiFile> {
+        let url = join_url(&self.base_url, "files")?;
+        let content = content.into();
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            let form = Form::new().text("purpose", purpose.as_str()).part(
+                "file",
+                Part::bytes(content.clone()).file_name(filename.to_owned()),
+            );
+
+            self.client
+                .post(url.clone())
+                .bearer_auth(api_key.expose_secret())
+                .multipart(form)
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Downloads the content of a file, such as the output of a batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails or the API returns a non-success status
+    /// code, such as for an unknown file.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn file_content(&self, file_id: &str) -> Result<Vec<u8>> {
+        let url = join_url_segments(&self.base_url, &["files", file_id, "content"])?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .get(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        let response = error_for_status(response).await?;
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Deletes an uploaded file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails or the API returns a non-success status
+    /// code, such as for an unknown file.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_file(&self, file_id: &str) -> Result<()> {
+        let url = join_url_segments(&self.base_url, &["files", file_id])?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .delete(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        error_for_status(response).await?;
+
+        Ok(())
+    }
+
+    /// Creates a batch running the requests of an uploaded input file against
+    /// `/v1/responses`, with a 24 hour completion window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn create_batch(
+        &self,
+        input_file_id: &str,
+        metadata: BTreeMap<String, String>,
+    ) -> Result<OpenaiBatch> {
+        let url = join_url(&self.base_url, "batches")?;
+        let mut payload = serde_json::json!({
+            "input_file_id": input_file_id,
+            "endpoint": BATCH_ENDPOINT,
+            "completion_window": "24h",
+        });
+        if !metadata.is_empty() {
+            payload["metadata"] = serde_json::json!(metadata);
+        }
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .bearer_auth(api_key.expose_secret())
+                .json(&payload)
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Uploads the input file of `requests`, built with [`batch_jsonl`], and creates a batch
+    /// running them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Openai::upload_file`] and [`Openai::create_batch`].
+    pub async fn submit_batch<I>(&self, requests: I) -> Result<OpenaiBatch>
+    where
+        I: IntoIterator<Item = (OpenaiModel, AiRequest)>,
+    {
+        let jsonl = batch_jsonl(requests)?;
+        let file = self
+            .upload_file("batch.jsonl", jsonl, FilePurpose::Batch)
+            .await?;
+
+        self.create_batch(&file.id, BTreeMap::new()).await
+    }
+
+    /// Retrieves a batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, such as for an unknown batch, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn retrieve_batch(&self, batch_id: &str) -> Result<OpenaiBatch> {
+        let url = join_url_segments(&self.base_url, &["batches", batch_id])?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .get(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Cancels a batch, returning it in its `cancelling` state. Requests that already ran
+    /// are kept in its output file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, such as for a finished batch, or the response body cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn cancel_batch(&self, batch_id: &str) -> Result<OpenaiBatch> {
+        let url = join_url_segments(&self.base_url, &["batches", batch_id, "cancel"])?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Polls a batch until it reaches a final status, and returns it.
+    ///
+    /// Expired and cancelled batches are returned, since the requests that ran before are
+    /// still in their output files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if retrieving the batch fails, its input file fails validation,
+    /// or the timeout of `poll` elapses first.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn wait_for_batch(&self, batch_id: &str, poll: PollOptions) -> Result<OpenaiBatch> {
+        let deadline = poll.timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let batch = self.retrieve_batch(batch_id).await?;
+
+            match batch.status() {
+                status if status.is_pending() => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("Batch {} is {:?}", batch_id, status);
+                }
+                BatchStatus::Failed => {
+                    return Err(Error::ProviderError {
+                        provider: "OpenAI".into(),
+                        error: batch
+                            .error_messages()
+                            .unwrap_or_else(|| format!("Batch {batch_id} failed")),
+                    });
+                }
+                _ => return Ok(batch),
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() + poll.interval > deadline) {
+                return Err(Error::ProviderError {
+                    provider: "OpenAI".into(),
+                    error: format!("Timed out waiting for batch {batch_id}"),
+                });
+            }
+
+            tokio::time::sleep(poll.interval).await;
+        }
+    }
+
+    /// Downloads the output and error files of a batch, and returns the result of every
+    /// request by `custom_id`.
+    ///
+    /// Failed requests are returned as an [`Error::ApiError`] with the status and body of
+    /// their response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if downloading a file fails or a line of a file cannot be parsed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, batch)))]
+    pub async fn batch_results(
+        &self,
+        batch: &OpenaiBatch,
+    ) -> Result<BTreeMap<String, Result<OpenaiResponse>>> {
+        let mut results = BTreeMap::new();
+
+        for file_id in [&batch.output_file_id, &batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let content = self.file_content(file_id).await?;
+
+            for line in content.split(|byte| *byte == b'\n') {
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+
+                let line: BatchOutputLine = serde_json::from_slice(line)?;
+                let (custom_id, result) = line.into_result();
+                results.insert(custom_id, result);
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_jsonl() {
+        let jsonl = batch_jsonl([
+            (
+                OpenaiModel::Gpt5Mini,
+                AiRequest {
+                    text: "First".into(),
+                    ..Default::default()
+                },
+            ),
+            (
+                OpenaiModel::Gpt41,
+                AiRequest {
+                    text: "Second".into(),
+                    ..Default::default()
+                },
+            ),
+        ])
+        .unwrap();
+
+        let lines = jsonl
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            [
+                serde_json::json!({
+                    "custom_id": "request-0",
+                    "method": "POST",
+                    "url": "/v1/responses",
+                    "body": {"model": "gpt-5-mini", "input": "First"}
+                }),
+                serde_json::json!({
+                    "custom_id": "request-1",
+                    "method": "POST",
+                    "url": "/v1/responses",
+                    "body": {"model": "gpt-4.1", "input": "Second"}
+                }),
+            ]
+        );
+    }
+}
@@ -7,49 +7,242 @@
 
 use std::{future::ready, sync::Arc};
 
-use eventsource_stream::Eventsource;
 use futures::{FutureExt, StreamExt, stream::BoxStream};
-use latchlm_core::{AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, Error, Result};
+use latchlm_core::{
+    AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, CredentialProvider, Error, ModelPricing,
+    PricingTable, Result, StaticCredential, error_for_status, join_url, join_url_segments,
+    parse_event_stream, parse_json, send_authenticated,
+};
 use latchlm_macros::AiModel;
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
 use secrecy::{ExposeSecret, SecretString};
 
+mod azure;
+pub use azure::*;
+
+mod background;
+pub use background::*;
+
+mod batch;
+pub use batch::*;
+
+mod request;
+use request::OpenaiRequest;
+pub use request::{
+    CodeInterpreterContainer, OpenaiRequestOptions, OpenaiTool, SearchContextSize, Truncation,
+    UserLocation,
+};
+
 mod response;
 pub use response::*;
 
 /// Variants representing supported OpenAI models.
 ///
 /// These variants map to the actual model identifiers used by the OpenAI API.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AiModel)]
+/// Models without a variant, such as those returned by [`Openai::models`],
+/// can be used through [`OpenaiModel::Custom`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, AiModel)]
 pub enum OpenaiModel {
-    #[model(id = "o3", name = "GPT-o3")]
+    #[model(
+        id = "o3",
+        name = "GPT-o3",
+        alias = "o3-2025-04-16",
+        context_window = 200_000,
+        max_output_tokens = 100_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpto3,
-    #[model(id = "o3-pro", name = "GPT-o3 Pro")]
+    #[model(
+        id = "o3-pro",
+        name = "GPT-o3 Pro",
+        alias = "o3-pro-2025-06-10",
+        context_window = 200_000,
+        max_output_tokens = 100_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = false
+    )]
     Gpto3Pro,
-    #[model(id = "o3-mini", name = "GPT-o3 Mini")]
+    #[model(
+        id = "o3-mini",
+        name = "GPT-o3 Mini",
+        alias = "o3-mini-2025-01-31",
+        context_window = 200_000,
+        max_output_tokens = 100_000,
+        input_modalities = "text",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpto3Mini,
-    #[model(id = "o4-mini", name = "GPT-o4 Mini")]
+    #[model(
+        id = "o4-mini",
+        name = "GPT-o4 Mini",
+        alias = "o4-mini-2025-04-16",
+        context_window = 200_000,
+        max_output_tokens = 100_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpto4Mini,
-    #[model(id = "gpt-5.1", name = "GPT-5.1")]
+    #[model(
+        id = "gpt-5.1",
+        name = "GPT-5.1",
+        alias = "gpt-5.1-2025-11-13",
+        context_window = 400_000,
+        max_output_tokens = 128_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpt51,
-    #[model(id = "gpt-5", name = "GPT-5")]
+    #[model(
+        id = "gpt-5",
+        name = "GPT-5",
+        alias = "gpt-5-2025-08-07",
+        context_window = 400_000,
+        max_output_tokens = 128_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpt5,
-    #[model(id = "gpt-5-mini", name = "GPT-5 Mini")]
+    #[model(
+        id = "gpt-5-mini",
+        name = "GPT-5 Mini",
+        alias = "gpt-5-mini-2025-08-07",
+        default,
+        context_window = 400_000,
+        max_output_tokens = 128_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpt5Mini,
-    #[model(id = "gpt-5-nano", name = "GPT-5 Nano")]
+    #[model(
+        id = "gpt-5-nano",
+        name = "GPT-5 Nano",
+        alias = "gpt-5-nano-2025-08-07",
+        context_window = 400_000,
+        max_output_tokens = 128_000,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = true,
+        streaming = true
+    )]
     Gpt5Nano,
-    #[model(id = "gpt-5-chat-latest", name = "GPT-5 Chat")]
+    #[model(
+        id = "gpt-5-chat-latest",
+        name = "GPT-5 Chat",
+        context_window = 128_000,
+        max_output_tokens = 16_384,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = false,
+        structured_output = false,
+        reasoning = false,
+        streaming = true
+    )]
     Gpt5Chat,
-    #[model(id = "gpt-4.1", name = "GPT-4.1")]
+    #[model(
+        id = "gpt-4.1",
+        name = "GPT-4.1",
+        alias = "gpt-4.1-2025-04-14",
+        context_window = 1_047_576,
+        max_output_tokens = 32_768,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Gpt41,
-    #[model(id = "gpt-4.1-mini", name = "GPT-4.1 Mini")]
+    #[model(
+        id = "gpt-4.1-mini",
+        name = "GPT-4.1 Mini",
+        alias = "gpt-4.1-mini-2025-04-14",
+        context_window = 1_047_576,
+        max_output_tokens = 32_768,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Gpt41Mini,
-    #[model(id = "gpt-4.1-nano", name = "GPT-4.1 Nano")]
+    #[model(
+        id = "gpt-4.1-nano",
+        name = "GPT-4.1 Nano",
+        alias = "gpt-4.1-nano-2025-04-14",
+        context_window = 1_047_576,
+        max_output_tokens = 32_768,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Gpt41Nano,
-    #[model(id = "gpt-4o", name = "GPT-4o")]
+    #[model(
+        id = "gpt-4o",
+        name = "GPT-4o",
+        alias = "gpt-4o-2024-11-20",
+        alias = "gpt-4o-2024-08-06",
+        alias = "gpt-4o-2024-05-13",
+        context_window = 128_000,
+        max_output_tokens = 16_384,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Gpt4o,
-    #[model(id = "gpt-4o-mini", name = "GPT-4o Mini")]
+    #[model(
+        id = "gpt-4o-mini",
+        name = "GPT-4o Mini",
+        alias = "gpt-4o-mini-2024-07-18",
+        context_window = 128_000,
+        max_output_tokens = 16_384,
+        input_modalities = "text, image",
+        output_modalities = "text",
+        tool_calling = true,
+        structured_output = true,
+        reasoning = false,
+        streaming = true
+    )]
     Gpt4oMini,
+    /// Any other model, identified by its id
+    #[model(other)]
+    Custom(String),
 }
 
 impl std::fmt::Display for OpenaiModel {
@@ -58,6 +251,44 @@ impl std::fmt::Display for OpenaiModel {
     }
 }
 
+impl OpenaiModel {
+    /// Returns the list price of the model, in US dollars per million tokens.
+    ///
+    /// Prices are those of the standard processing tier.
+    #[must_use]
+    pub fn pricing(&self) -> Option<ModelPricing> {
+        let pricing = match self {
+            Self::Gpto3 => ModelPricing::new(2.00, 8.00).with_cached_input(0.50),
+            Self::Gpto3Pro => ModelPricing::new(20.00, 80.00),
+            Self::Gpto3Mini => ModelPricing::new(1.10, 4.40).with_cached_input(0.55),
+            Self::Gpto4Mini => ModelPricing::new(1.10, 4.40).with_cached_input(0.275),
+            Self::Gpt51 | Self::Gpt5 | Self::Gpt5Chat => {
+                ModelPricing::new(1.25, 10.00).with_cached_input(0.125)
+            }
+            Self::Gpt5Mini => ModelPricing::new(0.25, 2.00).with_cached_input(0.025),
+            Self::Gpt5Nano => ModelPricing::new(0.05, 0.40).with_cached_input(0.005),
+            Self::Gpt41 => ModelPricing::new(2.00, 8.00).with_cached_input(0.50),
+            Self::Gpt41Mini => ModelPricing::new(0.40, 1.60).with_cached_input(0.10),
+            Self::Gpt41Nano => ModelPricing::new(0.10, 0.40).with_cached_input(0.025),
+            Self::Gpt4o => ModelPricing::new(2.50, 10.00).with_cached_input(1.25),
+            Self::Gpt4oMini => ModelPricing::new(0.15, 0.60).with_cached_input(0.075),
+            Self::Custom(_) => return None,
+        };
+
+        Some(pricing)
+    }
+
+    /// Returns a [`PricingTable`] with the list prices of all OpenAI models.
+    #[must_use]
+    pub fn pricing_table() -> PricingTable {
+        Self::variants()
+            .iter()
+            .filter_map(|variant| Self::try_from(variant.id.as_ref()).ok())
+            .filter_map(|model| Some((model.as_ref().to_owned(), model.pricing()?)))
+            .collect()
+    }
+}
+
 /// Errors that can occur when building a [`Openai`] client.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OpenaiError {
@@ -65,6 +296,8 @@ pub enum OpenaiError {
     MissingClientError,
     /// Returned when no API key is provided
     MissingApiKeyError,
+    /// Returned when no Azure OpenAI endpoint is provided
+    MissingEndpointError,
 }
 
 impl std::fmt::Display for OpenaiError {
@@ -72,6 +305,7 @@ impl std::fmt::Display for OpenaiError {
         match self {
             Self::MissingClientError => write!(f, "HTTP client is required"),
             Self::MissingApiKeyError => write!(f, "API key is required"),
+            Self::MissingEndpointError => write!(f, "Azure OpenAI endpoint is required"),
         }
     }
 }
@@ -87,6 +321,10 @@ impl From<OpenaiError> for Error {
                 provider: "OpenAI".into(),
                 error: "Missing request::Client".into(),
             },
+            OpenaiError::MissingEndpointError => Self::ProviderError {
+                provider: "OpenAI".into(),
+                error: "Missing Azure OpenAI endpoint".into(),
+            },
         }
     }
 }
@@ -95,7 +333,8 @@ impl From<OpenaiError> for Error {
 #[derive(Debug, Clone, Default)]
 pub struct OpenaiBuilder {
     client: Option<reqwest::Client>,
-    api_key: Option<SecretString>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    base_url: Option<reqwest::Url>,
 }
 
 impl OpenaiBuilder {
@@ -134,8 +373,46 @@ impl OpenaiBuilder {
     ///
     /// The updated `OpenaiBuilder` instance.
     #[must_use]
-    pub fn api_key(mut self, api_key: SecretString) -> Self {
-        self.api_key = Some(api_key);
+    pub fn api_key(self, api_key: SecretString) -> Self {
+        self.credentials(StaticCredential::new(api_key))
+    }
+
+    /// Set the provider consulted for the API key before every request.
+    ///
+    /// Use this instead of [`OpenaiBuilder::api_key`] to rotate keys without rebuilding the
+    /// client.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The updated `OpenaiBuilder` instance.
+    #[must_use]
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    /// Set the base URL the API paths, such as `responses`, are appended to.
+    ///
+    /// Defaults to `https://api.openai.com/v1/`. Any path prefix is kept, so requests can
+    /// be routed through proxies, gateways and OpenAI-compatible servers.
+    ///
+    /// The base URL must not include the `responses` endpoint itself: [`OpenaiBuilder::build`]
+    /// rejects a base URL ending with `/responses`.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the API.
+    ///
+    /// # Returns
+    ///
+    /// The updated `OpenaiBuilder` instance.
+    #[must_use]
+    pub fn base_url(mut self, base_url: reqwest::Url) -> Self {
+        self.base_url = Some(base_url);
         self
     }
 
@@ -143,7 +420,7 @@ impl OpenaiBuilder {
     pub fn api_key_from_env(mut self) -> std::result::Result<Self, std::env::VarError> {
         let api_key = std::env::var("OPENAI_API_KEY")?;
 
-        self.api_key = Some(SecretString::from(api_key));
+        self.credentials = Some(Arc::new(StaticCredential::new(SecretString::from(api_key))));
         Ok(self)
     }
 
@@ -151,11 +428,33 @@ impl OpenaiBuilder {
     ///
     /// # Returns
     ///
-    /// A new `Openai` client.
+    /// A new `Openai` client, or an error if the client or API key are missing, or the
+    /// base URL cannot be a base or ends with the `responses` endpoint.
     pub fn build(self) -> Result<Openai> {
         let client = self.client.ok_or(OpenaiError::MissingClientError)?;
-        let api_key = self.api_key.ok_or(OpenaiError::MissingApiKeyError)?;
-        Ok(Openai::new(client, api_key))
+        let credentials = self.credentials.ok_or(OpenaiError::MissingApiKeyError)?;
+
+        let base_url = match self.base_url {
+            // Base URLs used to be the full `responses` endpoint, which would now be doubled
+            Some(base_url)
+                if base_url.cannot_be_a_base()
+                    || base_url
+                        .path()
+                        .trim_end_matches('/')
+                        .ends_with("/responses") =>
+            {
+                return Err(Error::InvalidUrlError(base_url.to_string()));
+            }
+            Some(base_url) => base_url,
+            None => reqwest::Url::parse(Openai::BASE_URL)
+                .map_err(|_| Error::InvalidUrlError(Openai::BASE_URL.to_owned()))?,
+        };
+
+        Ok(Openai {
+            client,
+            base_url,
+            credentials,
+        })
     }
 }
 
@@ -164,11 +463,13 @@ impl OpenaiBuilder {
 pub struct Openai {
     client: reqwest::Client,
     base_url: reqwest::Url,
-    api_key: Arc<SecretString>,
+    credentials: Arc<dyn CredentialProvider>,
 }
 
 impl Openai {
-    const BASE_URL: &str = "https://api.openai.com/v1/responses";
+    // The API root the endpoint paths are appended to. Earlier versions used the full
+    // `responses` endpoint as the base URL.
+    const BASE_URL: &str = "https://api.openai.com/v1/";
 
     /// Create a new OpenAI client.
     ///
@@ -186,14 +487,16 @@ impl Openai {
         Self {
             client,
             base_url: reqwest::Url::parse(Self::BASE_URL).expect("Failed to parse base URL"),
-            api_key: Arc::new(api_key),
+            credentials: Arc::new(StaticCredential::new(api_key)),
         }
     }
 
-    /// Create a new OpenAI client with a custom base URL for testing.
+    /// Create a new OpenAI client with a custom base URL.
     ///
-    /// This constructor is intended exclusively for testing and mocking scenarios
-    /// and should **never** be used in production code.
+    /// The API paths, such as `responses` and `models`, are appended to the base URL,
+    /// keeping any path prefix it has. Unlike in earlier versions, the base URL must not
+    /// include the `responses` endpoint: pass `https://api.openai.com/v1/`, not
+    /// `https://api.openai.com/v1/responses`.
     ///
     /// # Arguments
     ///
@@ -201,21 +504,20 @@ impl Openai {
     /// * `base_url` - The base URL to use for making requests.
     /// * `api_key` - The API key to use for authentication.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// A new `Openai` client.
-    #[cfg(feature = "test-utils")]
-    #[must_use]
+    /// Returns [`Error::InvalidUrlError`] if the base URL cannot be a base or ends with the
+    /// `responses` endpoint.
     pub fn new_with_base_url(
         client: reqwest::Client,
         base_url: reqwest::Url,
         api_key: SecretString,
-    ) -> Self {
-        Self {
-            client,
-            api_key: Arc::new(api_key),
-            base_url,
-        }
+    ) -> Result<Self> {
+        Self::builder()
+            .client(client)
+            .api_key(api_key)
+            .base_url(base_url)
+            .build()
     }
 
     /// Create a new [`OpenaiBuilder`] instance.
@@ -273,6 +575,7 @@ impl Openai {
     ///         OpenaiModel::Gpt4o,
     ///         AiRequest {
     ///             text: "Hello".into(),
+    ///             ..Default::default()
     ///         }
     ///     ).await?;
     ///
@@ -282,45 +585,142 @@ impl Openai {
     /// ```
     ///
     /// [`Error`]: latchlm_core::Error
-    #[allow(clippy::expect_used)]
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn request(&self, model: OpenaiModel, request: AiRequest) -> Result<OpenaiResponse> {
-        let mut header_map = HeaderMap::new();
-        header_map.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        header_map.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.api_key.expose_secret()))
-                .expect("Invalid header value for Authorization"),
-        );
-
-        let request = serde_json::json!({"model": model.as_ref(), "input": request.text});
-
-        let response = self
-            .client
-            .post(self.base_url.clone())
-            .headers(header_map)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await?;
-
-            #[cfg(feature = "tracing")]
-            tracing::error!("API error: {}", &message);
+        self.request_with_options(model, request, OpenaiRequestOptions::default())
+            .await
+    }
 
-            return Err(Error::ApiError { status, message });
+    /// Sends a request to the OpenAI Responses API with OpenAI-specific options.
+    ///
+    /// Setting [`OpenaiRequestOptions::previous_response_id`] continues the conversation of
+    /// a stored response, so only the new input has to be sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Openai::request`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn request_with_options(
+        &self,
+        model: OpenaiModel,
+        request: AiRequest,
+        options: OpenaiRequestOptions,
+    ) -> Result<OpenaiResponse> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
         }
 
-        let bytes = response.bytes().await?;
+        let url = join_url(&self.base_url, "responses")?;
+        let request = OpenaiRequest::new(model, request, false).with_options(options);
 
-        #[cfg(feature = "tracing")]
-        tracing::debug!("Received bytes: {:?}", bytes);
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .bearer_auth(api_key.expose_secret())
+                .json(&request)
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Lists the models available to the API key.
+    ///
+    /// Use [`OpenaiModelInfo::model`] to make requests with any of the returned models.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if:
+    /// - The HTTP request fails (network issues, timeout, etc.)
+    /// - The API returns a non-success status code
+    /// - The response body cannot be parsed as valid JSON
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn models(&self) -> Result<Vec<OpenaiModelInfo>> {
+        let url = join_url(&self.base_url, "models")?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .get(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        let response: OpenaiModelsList = parse_json(response).await?;
+
+        Ok(response.data)
+    }
+
+    /// Retrieves a stored response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, such as `404` for an unknown or deleted response, or the response body cannot
+    /// be parsed.
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn retrieve_response(&self, response_id: &str) -> Result<OpenaiResponse> {
+        let url = join_url_segments(&self.base_url, &["responses", response_id])?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .get(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        parse_json(response).await
+    }
+
+    /// Deletes a stored response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails or the API returns a non-success status
+    /// code.
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_response(&self, response_id: &str) -> Result<()> {
+        let url = join_url_segments(&self.base_url, &["responses", response_id])?;
+
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .delete(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
+
+        error_for_status(response).await?;
+
+        Ok(())
+    }
+
+    /// Cancels a response running in the background, returning it in its cancelled state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails, the API returns a non-success status
+    /// code, such as for a response that was not created in the background, or the response
+    /// body cannot be parsed.
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn cancel_response(&self, response_id: &str) -> Result<OpenaiResponse> {
+        let url = join_url_segments(&self.base_url, &["responses", response_id, "cancel"])?;
 
-        let response: OpenaiResponse = serde_json::from_slice(&bytes)?;
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .bearer_auth(api_key.expose_secret())
+        })
+        .await?;
 
-        Ok(response)
+        parse_json(response).await
     }
 
     /// Sends a streaming request to the OpenAI and returns a stream of responses.
@@ -329,64 +729,53 @@ impl Openai {
     ///
     /// * `model` - The model to use for the request.
     /// * `request` - The request to send.
-    #[allow(clippy::expect_used)]
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn streaming_request(
         &self,
         model: OpenaiModel,
         request: AiRequest,
     ) -> Result<BoxStream<'_, Result<OpenaiStreamResponse>>> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.api_key.expose_secret()))
-                .expect("Invalid header value for Authorization"),
-        );
-
-        let request =
-            serde_json::json!({"model": model.as_ref(), "input": request.text, "stream": true});
-
-        let response = self
-            .client
-            .post(self.base_url.clone())
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await?;
-
-            #[cfg(feature = "tracing")]
-            tracing::error!("API error: {}", &message);
+        self.streaming_request_with_options(model, request, OpenaiRequestOptions::default())
+            .await
+    }
 
-            return Err(Error::ApiError { status, message });
+    /// Sends a streaming request to the OpenAI Responses API with OpenAI-specific options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the HTTP request fails or the API returns a non-success
+    /// status code. The stream yields an [`Error::ProviderError`] for `error` and
+    /// `response.failed` events.
+    ///
+    /// [`Error`]: latchlm_core::Error
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn streaming_request_with_options(
+        &self,
+        model: OpenaiModel,
+        request: AiRequest,
+        options: OpenaiRequestOptions,
+    ) -> Result<BoxStream<'_, Result<OpenaiStreamResponse>>> {
+        #[cfg(feature = "tracing")]
+        if let Some(note) = model.model_id().deprecated {
+            tracing::warn!("Model {} is deprecated: {}", model.as_ref(), note);
         }
 
-        let stream = response.bytes_stream().eventsource().map(|result| {
-            let response = match result {
-                Ok(response) => {
-                    #[cfg(feature = "tracing")]
-                    tracing::debug!("Received response: {:?}", response);
+        let url = join_url(&self.base_url, "responses")?;
+        let request = OpenaiRequest::new(model, request, true).with_options(options);
 
-                    response
-                }
-                Err(err) => {
-                    #[cfg(feature = "tracing")]
-                    tracing::error!("Error receiving response: {}", err);
-
-                    return Err(Error::ProviderError {
-                        provider: "OpenAI".into(),
-                        error: err.to_string(),
-                    });
-                }
-            };
-            let response: OpenaiStreamResponse = serde_json::from_str(&response.data)?;
-            Ok(response)
-        });
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .bearer_auth(api_key.expose_secret())
+                .json(&request)
+        })
+        .await?;
+
+        let stream = parse_event_stream(response, "OpenAI").await?;
 
-        Ok(Box::pin(stream))
+        Ok(stream
+            .map(|event| event.and_then(OpenaiStreamResponse::error_for_event))
+            .boxed())
     }
 }
 
@@ -442,6 +831,7 @@ impl AiProvider for Openai {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use latchlm_core::{Modality, ModelCapabilities};
     use proptest::prelude::*;
 
     proptest! {
@@ -468,16 +858,60 @@ mod tests {
         }
 
         #[test]
-        fn test_openai_model_try_from_invalid(model_str in "\\PC*") {
-            let valid_ids: Vec<_> = OpenaiModel::variants()
-                .iter()
-                .map(|v| v.id.clone())
-                .collect();
-
-            prop_assume!(!valid_ids.contains(&model_str.clone().into()));
+        fn test_openai_model_try_from_custom(model_str in "\\PC*") {
+            prop_assume!(!model_str.trim().is_empty());
+            // Excludes the ids and aliases of the known variants
+            prop_assume!(!matches!(
+                OpenaiModel::try_from(model_str.as_str()),
+                Ok(model) if !matches!(model, OpenaiModel::Custom(_))
+            ));
+
+            let model = OpenaiModel::try_from(model_str.as_str()).unwrap();
+            prop_assert_eq!(model.as_ref(), model_str.as_str());
+            prop_assert_eq!(model, OpenaiModel::Custom(model_str));
+        }
 
+        #[test]
+        fn test_openai_model_try_from_blank(model_str in "\\s*") {
             let err = OpenaiModel::try_from(model_str.as_str()).unwrap_err();
             prop_assert_eq!(err.to_string(), format!("Invalid model name: {}", model_str));
         }
     }
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = OpenaiModel::Gpt41.capabilities();
+        assert_eq!(capabilities.context_window, Some(1_047_576));
+        assert!(capabilities.accepts(Modality::Image));
+        assert_eq!(capabilities.tool_calling, Some(true));
+
+        for variant in OpenaiModel::variants() {
+            assert!(variant.capabilities.context_window.is_some());
+        }
+
+        let custom = OpenaiModel::Custom("custom-model".to_owned());
+        assert_eq!(custom.capabilities(), ModelCapabilities::new());
+    }
+
+    #[test]
+    fn test_aliases_and_defaults() {
+        let model = OpenaiModel::try_from("gpt-4o-2024-08-06").unwrap();
+        assert_eq!(model, OpenaiModel::Gpt4o);
+        assert_eq!(model.as_ref(), "gpt-4o");
+
+        assert_eq!(OpenaiModel::default(), OpenaiModel::Gpt5Mini);
+    }
+
+    #[test]
+    fn test_pricing_table_covers_all_models() {
+        let table = OpenaiModel::pricing_table();
+
+        for variant in OpenaiModel::variants() {
+            assert!(
+                table.get(&variant.id).is_some(),
+                "missing price for {}",
+                variant.id
+            );
+        }
+    }
 }
@@ -0,0 +1,353 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! This module contains the structs used to serialize
+//! the OpenAI API requests
+
+use std::collections::BTreeMap;
+
+use latchlm_core::{AiRequest, ReasoningEffort, ReasoningOptions};
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+
+/// How the Responses API handles an input exceeding the context window of the model.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Truncation {
+    /// Drop items from the middle of the conversation to fit the context window
+    Auto,
+    /// Fail the request with a `400` error
+    Disabled,
+}
+
+/// How much context the web search tool retrieves for the model.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchContextSize {
+    Low,
+    Medium,
+    High,
+}
+
+/// The approximate location of the user, used to refine web search results.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(tag = "type", rename = "approximate")]
+pub struct UserLocation {
+    /// The two-letter ISO country code, such as `US`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    /// The IANA timezone, such as `America/Chicago`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+/// The container the code interpreter runs code in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeInterpreterContainer {
+    /// An existing container
+    Id(String),
+    /// A new container created for the request, with the given uploaded files
+    Auto { file_ids: Vec<String> },
+}
+
+impl Default for CodeInterpreterContainer {
+    fn default() -> Self {
+        Self::Auto {
+            file_ids: Vec::new(),
+        }
+    }
+}
+
+impl Serialize for CodeInterpreterContainer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Id(id) => serializer.serialize_str(id),
+            Self::Auto { file_ids } => {
+                let mut container = serializer.serialize_struct("CodeInterpreterContainer", 2)?;
+                container.serialize_field("type", "auto")?;
+                if file_ids.is_empty() {
+                    container.skip_field("file_ids")?;
+                } else {
+                    container.serialize_field("file_ids", file_ids)?;
+                }
+                container.end()
+            }
+        }
+    }
+}
+
+/// A tool hosted by OpenAI that the model may call while generating a response.
+///
+/// The calls appear in [`OpenaiResponse::output`], and the citations of the search tools in
+/// [`OpenaiResponse::annotations`].
+///
+/// [`OpenaiResponse::output`]: crate::OpenaiResponse::output
+/// [`OpenaiResponse::annotations`]: crate::OpenaiResponse::annotations
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum OpenaiTool {
+    /// Searches the web
+    WebSearch {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        search_context_size: Option<SearchContextSize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user_location: Option<UserLocation>,
+    },
+    /// Searches files uploaded to vector stores
+    FileSearch {
+        vector_store_ids: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_num_results: Option<u32>,
+    },
+    /// Runs Python code in a sandboxed container
+    CodeInterpreter { container: CodeInterpreterContainer },
+}
+
+impl OpenaiTool {
+    /// Returns the web search tool with its default settings.
+    #[must_use]
+    pub fn web_search() -> Self {
+        Self::WebSearch {
+            search_context_size: None,
+            user_location: None,
+        }
+    }
+
+    /// Returns the file search tool searching the given vector stores.
+    pub fn file_search(vector_store_ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::FileSearch {
+            vector_store_ids: vector_store_ids.into_iter().map(Into::into).collect(),
+            max_num_results: None,
+        }
+    }
+
+    /// Returns the code interpreter tool running in a new container.
+    #[must_use]
+    pub fn code_interpreter() -> Self {
+        Self::CodeInterpreter {
+            container: CodeInterpreterContainer::default(),
+        }
+    }
+}
+
+/// Options of a Responses API request that have no provider-agnostic equivalent.
+///
+/// Pass them to [`Openai::request_with_options`] to continue a conversation from the state
+/// the API stored for a previous response, instead of sending the whole history again.
+///
+/// [`Openai::request_with_options`]: crate::Openai::request_with_options
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct OpenaiRequestOptions {
+    /// The id of the stored response this request continues
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+    /// Whether the response is stored for later retrieval, which the API does by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+    /// Up to 16 key-value pairs attached to the response
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// How an input exceeding the context window is handled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<Truncation>,
+    /// Whether the response is generated in the background, which requires it to be stored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<bool>,
+    /// The hosted tools the model may call
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<OpenaiTool>,
+    /// Additional data to return, such as `file_search_call.results` or
+    /// `code_interpreter_call.outputs`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct ReasoningConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effort: Option<ReasoningEffort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<&'static str>,
+}
+
+impl From<ReasoningOptions> for ReasoningConfig {
+    fn from(value: ReasoningOptions) -> Self {
+        // The Responses API has no reasoning token budget, so `budget_tokens` is ignored.
+        Self {
+            effort: value.effort,
+            summary: value.include_reasoning.then_some("auto"),
+        }
+    }
+}
+
+/// The body of a request to the Responses API.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OpenaiRequest {
+    model: String,
+    input: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<ReasoningConfig>,
+    #[serde(flatten)]
+    options: OpenaiRequestOptions,
+}
+
+impl OpenaiRequest {
+    // The Responses API has no candidate count, so `candidate_count` is ignored.
+    pub(crate) fn new(model: impl AsRef<str>, request: AiRequest, stream: bool) -> Self {
+        Self {
+            model: model.as_ref().to_owned(),
+            input: request.text,
+            stream,
+            reasoning: request.reasoning.map(Into::into),
+            options: OpenaiRequestOptions::default(),
+        }
+    }
+
+    pub(crate) fn with_options(mut self, options: OpenaiRequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::OpenaiModel;
+
+    #[test]
+    fn test_serialize_text_only() {
+        let request = OpenaiRequest::new(
+            OpenaiModel::Gpt4o,
+            AiRequest {
+                text: "Hello".into(),
+                ..Default::default()
+            },
+            false,
+        );
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({"model": "gpt-4o", "input": "Hello"})
+        );
+    }
+
+    #[test]
+    fn test_serialize_reasoning() {
+        let request = OpenaiRequest::new(
+            OpenaiModel::Gpto3,
+            AiRequest {
+                text: "Hello".into(),
+                reasoning: Some(ReasoningOptions {
+                    effort: Some(ReasoningEffort::Low),
+                    budget_tokens: Some(1024),
+                    include_reasoning: true,
+                }),
+                ..Default::default()
+            },
+            true,
+        );
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({
+                "model": "o3",
+                "input": "Hello",
+                "stream": true,
+                "reasoning": {"effort": "low", "summary": "auto"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_options() {
+        let request = OpenaiRequest::new(
+            OpenaiModel::Gpt5Mini,
+            AiRequest {
+                text: "And then?".into(),
+                ..Default::default()
+            },
+            false,
+        )
+        .with_options(OpenaiRequestOptions {
+            previous_response_id: Some("resp_1".into()),
+            store: Some(false),
+            metadata: BTreeMap::from([("session".into(), "42".into())]),
+            truncation: Some(Truncation::Auto),
+            background: Some(true),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({
+                "model": "gpt-5-mini",
+                "input": "And then?",
+                "previous_response_id": "resp_1",
+                "store": false,
+                "metadata": {"session": "42"},
+                "truncation": "auto",
+                "background": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_tools() {
+        let request = OpenaiRequest::new(
+            OpenaiModel::Gpt41,
+            AiRequest {
+                text: "Hello".into(),
+                ..Default::default()
+            },
+            false,
+        )
+        .with_options(OpenaiRequestOptions {
+            tools: vec![
+                OpenaiTool::WebSearch {
+                    search_context_size: Some(SearchContextSize::Low),
+                    user_location: Some(UserLocation {
+                        country: Some("GB".into()),
+                        ..Default::default()
+                    }),
+                },
+                OpenaiTool::FileSearch {
+                    vector_store_ids: vec!["vs_1".into()],
+                    max_num_results: Some(5),
+                },
+                OpenaiTool::code_interpreter(),
+                OpenaiTool::CodeInterpreter {
+                    container: CodeInterpreterContainer::Id("cntr_1".into()),
+                },
+            ],
+            include: vec!["file_search_call.results".into()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({
+                "model": "gpt-4.1",
+                "input": "Hello",
+                "tools": [
+                    {
+                        "type": "web_search",
+                        "search_context_size": "low",
+                        "user_location": {"type": "approximate", "country": "GB"}
+                    },
+                    {"type": "file_search", "vector_store_ids": ["vs_1"], "max_num_results": 5},
+                    {"type": "code_interpreter", "container": {"type": "auto"}},
+                    {"type": "code_interpreter", "container": "cntr_1"}
+                ],
+                "include": ["file_search_call.results"]
+            })
+        );
+    }
+}
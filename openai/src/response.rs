@@ -5,41 +5,210 @@
 //! This module contains the structs used to deserialize
 //! the OpenAI API responses
 
-use latchlm_core::{AiResponse, TokenUsage};
+use latchlm_core::{AiResponse, Candidate, Citation, Error, FinishReason, Result, TokenUsage};
 use serde::{Deserialize, Serialize};
 
+use crate::OpenaiModel;
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 pub struct Content {
     #[serde(rename = "type")]
     kind: String,
     text: String,
-    annotations: Vec<String>,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
     logprobs: Option<Vec<serde_json::Value>>,
 }
 
+impl Content {
+    /// Returns the text of the content part.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the annotations of the text, such as the citations of the hosted tools.
+    #[must_use]
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+/// Returns the characters of `text` from `start` to `end`, exclusive.
+fn char_span(text: &str, start: u64, end: u64) -> String {
+    text.chars()
+        .zip(0..)
+        .filter(|(_, index)| (start..end).contains(index))
+        .map(|(char, _)| char)
+        .collect()
+}
+
+/// An annotation of a span of output text.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-#[serde(untagged)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Annotation {
+    /// A citation of a web page found by the web search tool
+    UrlCitation {
+        url: String,
+        title: String,
+        start_index: u64,
+        end_index: u64,
+    },
+    /// A citation of a file found by the file search tool
+    FileCitation {
+        file_id: String,
+        #[serde(default)]
+        filename: Option<String>,
+        index: u64,
+    },
+    /// A citation of a file created by the code interpreter in its container
+    ContainerFileCitation {
+        container_id: String,
+        file_id: String,
+        #[serde(default)]
+        filename: Option<String>,
+        start_index: u64,
+        end_index: u64,
+    },
+    /// The path of a file generated by the model
+    FilePath { file_id: String, index: u64 },
+    /// Any annotation that is not handled by this crate
+    #[serde(other)]
+    Other,
+}
+
+/// An item of the output of a response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum Output {
+    /// A message of the model
+    #[serde(rename = "message")]
     Content {
-        #[serde(rename = "type")]
-        kind: String,
         id: String,
         status: Option<String>,
         role: Option<String>,
         content: Vec<Content>,
     },
+    /// The reasoning summary of a reasoning model
+    #[serde(rename = "reasoning")]
     Summary {
         id: String,
-        summary: Vec<serde_json::Value>,
-        #[serde(rename = "type")]
-        kind: String,
+        summary: Vec<SummaryText>,
+    },
+    /// A call of the web search tool
+    WebSearchCall(WebSearchCall),
+    /// A call of the file search tool
+    FileSearchCall(FileSearchCall),
+    /// A call of the code interpreter tool
+    CodeInterpreterCall(CodeInterpreterCall),
+    /// Any output item that is not handled by this crate
+    #[serde(other)]
+    Other,
+}
+
+/// A call of the web search tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebSearchCall {
+    pub id: String,
+    pub status: String,
+    /// The action the model took, if the API reports it
+    #[serde(default)]
+    pub action: Option<WebSearchAction>,
+}
+
+/// An action taken by the web search tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum WebSearchAction {
+    /// A search query
+    Search {
+        #[serde(default)]
+        query: Option<String>,
+    },
+    /// The opening of a page
+    OpenPage {
+        #[serde(default)]
+        url: Option<String>,
     },
+    /// A search for a pattern within a page
+    Find {
+        #[serde(default)]
+        url: Option<String>,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+    /// Any action that is not handled by this crate
+    #[serde(other)]
+    Other,
+}
+
+/// A call of the file search tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileSearchCall {
+    pub id: String,
+    pub status: String,
+    /// The queries used to search the files
+    #[serde(default)]
+    pub queries: Vec<String>,
+    /// The matching chunks, only returned when `file_search_call.results` is included
+    #[serde(default)]
+    pub results: Option<Vec<FileSearchResult>>,
+}
+
+/// A chunk of a file matching a file search.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileSearchResult {
+    pub file_id: String,
+    pub filename: String,
+    pub score: f64,
+    pub text: String,
+    #[serde(default)]
+    pub attributes: Option<serde_json::Value>,
+}
+
+/// A call of the code interpreter tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CodeInterpreterCall {
+    pub id: String,
+    pub status: String,
+    /// The code that was run
+    #[serde(default)]
+    pub code: Option<String>,
+    /// The id of the container the code ran in
+    pub container_id: String,
+    /// The outputs of the code, only returned when `code_interpreter_call.outputs` is included
+    #[serde(default)]
+    pub outputs: Option<Vec<CodeInterpreterOutput>>,
+}
+
+/// An output of code run by the code interpreter.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum CodeInterpreterOutput {
+    /// The logs printed by the code
+    Logs { logs: String },
+    /// An image generated by the code
+    Image { url: String },
+    /// Any output that is not handled by this crate
+    #[serde(other)]
+    Other,
+}
+
+/// A part of the reasoning summary of a reasoning model.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct SummaryText {
+    #[serde(rename = "type")]
+    kind: String,
+    text: String,
 }
 
 impl Default for Output {
     fn default() -> Self {
         Self::Content {
-            kind: String::new(),
             id: String::new(),
             status: None,
             role: None,
@@ -76,6 +245,12 @@ pub struct OutputTokensDetails {
     reasoning_tokens: u64,
 }
 
+/// Details on why a response is incomplete.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct IncompleteDetails {
+    pub reason: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 pub struct Usage {
     input_tokens: u64,
@@ -85,6 +260,48 @@ pub struct Usage {
     total_tokens: u64,
 }
 
+/// The lifecycle status of a response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ResponseStatus {
+    /// The response is waiting to be processed in the background
+    Queued,
+    /// The response is being generated
+    InProgress,
+    /// The response was generated in full
+    Completed,
+    /// The response stopped early, such as at the output token limit
+    Incomplete,
+    /// The response could not be generated
+    Failed,
+    /// The background response was cancelled
+    Cancelled,
+    /// A status this crate does not know about
+    Other(String),
+}
+
+impl ResponseStatus {
+    /// Returns whether the response is still queued or being generated.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Queued | Self::InProgress)
+    }
+}
+
+impl From<&str> for ResponseStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "queued" => Self::Queued,
+            "in_progress" => Self::InProgress,
+            "completed" => Self::Completed,
+            "incomplete" => Self::Incomplete,
+            "failed" => Self::Failed,
+            "cancelled" => Self::Cancelled,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
 /// Represents the response from the OpenAI API.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct OpenaiResponse {
@@ -94,7 +311,7 @@ pub struct OpenaiResponse {
     status: String,
     background: Option<bool>,
     error: Option<serde_json::Value>,
-    incomplete_details: Option<serde_json::Value>,
+    incomplete_details: Option<IncompleteDetails>,
     input: Option<Vec<serde_json::Value>>,
     instructions: Option<String>,
     max_output_tokens: Option<u64>,
@@ -120,21 +337,177 @@ pub struct OpenaiResponse {
     metadata: serde_json::Value,
 }
 
+impl From<&Usage> for TokenUsage {
+    fn from(value: &Usage) -> Self {
+        Self {
+            input_tokens: Some(value.input_tokens),
+            cached_input_tokens: value
+                .input_tokens_details
+                .as_ref()
+                .map(|details| details.cached_tokens),
+            output_tokens: Some(value.output_tokens),
+            reasoning_tokens: value
+                .output_tokens_details
+                .as_ref()
+                .map(|details| details.reasoning_tokens),
+            total_tokens: Some(value.total_tokens),
+            ..Default::default()
+        }
+    }
+}
+
 impl From<OpenaiResponse> for AiResponse {
     fn from(value: OpenaiResponse) -> Self {
-        let token_usage = TokenUsage {
-            input_tokens: value.usage.as_ref().map(|usage| usage.input_tokens),
-            output_tokens: value.usage.as_ref().map(|usage| usage.output_tokens),
-            total_tokens: value.usage.as_ref().map(|usage| usage.total_tokens),
-        };
+        let text = value.extract_text();
+        let reasoning = value.extract_reasoning();
+        let finish_reason = value.finish_reason();
+
         Self {
-            text: value.extract_text(),
-            token_usage,
+            token_usage: value.usage.as_ref().map(Into::into).unwrap_or_default(),
+            safety: None,
+            // The Responses API always generates a single candidate
+            candidates: vec![Candidate {
+                index: 0,
+                text: text.clone(),
+                reasoning: reasoning.clone(),
+                finish_reason: finish_reason.clone(),
+            }],
+            citations: value.citations(),
+            text,
+            reasoning,
+            finish_reason,
         }
     }
 }
 
 impl OpenaiResponse {
+    /// Returns the id of the response, used to retrieve it or continue its conversation.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the status of the response.
+    #[must_use]
+    pub fn status(&self) -> ResponseStatus {
+        self.status.as_str().into()
+    }
+
+    /// Returns whether the response was created to run in the background.
+    #[must_use]
+    pub fn background(&self) -> bool {
+        self.background.unwrap_or(false)
+    }
+
+    /// Returns the message of the error that made the response fail, if any.
+    #[must_use]
+    pub fn error_message(&self) -> Option<String> {
+        let error = self.error.as_ref().filter(|error| !error.is_null())?;
+
+        Some(
+            error
+                .get("message")
+                .and_then(serde_json::Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_owned),
+        )
+    }
+
+    /// Returns the id of the response this response continues, if any.
+    #[must_use]
+    pub fn previous_response_id(&self) -> Option<&str> {
+        self.previous_response_id.as_deref()
+    }
+
+    /// Returns whether the response is stored for later retrieval.
+    #[must_use]
+    pub fn store(&self) -> bool {
+        self.store
+    }
+
+    /// Returns the metadata attached to the response.
+    #[must_use]
+    pub fn metadata(&self) -> &serde_json::Value {
+        &self.metadata
+    }
+
+    /// Returns the normalized reason the response stopped generating.
+    ///
+    /// Returns `None` while the response is still queued or in progress.
+    #[must_use]
+    pub fn finish_reason(&self) -> Option<FinishReason> {
+        match self.status.as_str() {
+            "completed" => Some(FinishReason::Stop),
+            "incomplete" => {
+                let reason = self
+                    .incomplete_details
+                    .as_ref()
+                    .map(|details| details.reason.as_str());
+
+                Some(match reason {
+                    Some("max_output_tokens") => FinishReason::Length,
+                    Some("content_filter") => FinishReason::ContentFilter,
+                    Some(other) => FinishReason::Other(other.to_owned()),
+                    None => FinishReason::Other("incomplete".to_owned()),
+                })
+            }
+            "queued" | "in_progress" => None,
+            other => Some(FinishReason::Other(other.to_owned())),
+        }
+    }
+
+    /// Returns the output items of the response, including the calls of hosted tools.
+    #[must_use]
+    pub fn output(&self) -> &[Output] {
+        &self.output
+    }
+
+    /// Returns the annotations of all output messages, such as the citations of the hosted
+    /// tools, in order.
+    #[must_use]
+    pub fn annotations(&self) -> Vec<&Annotation> {
+        self.output
+            .iter()
+            .filter_map(|output| match output {
+                Output::Content { content, .. } => Some(content),
+                _ => None,
+            })
+            .flatten()
+            .flat_map(|content| &content.annotations)
+            .collect()
+    }
+
+    /// Returns the URL citations of all output messages as provider-agnostic citations.
+    #[must_use]
+    pub fn citations(&self) -> Vec<Citation> {
+        self.output
+            .iter()
+            .filter_map(|output| match output {
+                Output::Content { content, .. } => Some(content),
+                _ => None,
+            })
+            .flatten()
+            .flat_map(|content| {
+                content
+                    .annotations
+                    .iter()
+                    .filter_map(|annotation| match annotation {
+                        Annotation::UrlCitation {
+                            url,
+                            title,
+                            start_index,
+                            end_index,
+                        } => Some(Citation {
+                            url: url.clone(),
+                            title: Some(title.clone()),
+                            text: Some(char_span(&content.text, *start_index, *end_index)),
+                        }),
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the text of all output messages, exactly as generated.
     #[must_use]
     pub fn extract_text(&self) -> String {
         self.output
@@ -142,11 +515,32 @@ impl OpenaiResponse {
             .flat_map(|output| match output {
                 Output::Content {
                     content: contents, ..
-                } => contents.iter().map(|c| c.text.clone()).collect::<Vec<_>>(),
+                } => contents.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    /// Returns the reasoning summary of the response, if any.
+    ///
+    /// Summaries are only returned when `include_reasoning` is set in the request's
+    /// [`ReasoningOptions`]. Multiple summary parts are separated by a blank line.
+    ///
+    /// [`ReasoningOptions`]: latchlm_core::ReasoningOptions
+    #[must_use]
+    pub fn extract_reasoning(&self) -> Option<String> {
+        let summaries = self
+            .output
+            .iter()
+            .flat_map(|output| match output {
+                Output::Summary { summary, .. } => {
+                    summary.iter().map(|s| s.text.as_str()).collect()
+                }
                 _ => vec![],
             })
-            .collect::<Vec<_>>()
-            .join(" ")
+            .collect::<Vec<_>>();
+
+        (!summaries.is_empty()).then(|| summaries.join("\n\n"))
     }
 }
 
@@ -175,7 +569,8 @@ pub struct Part {
     #[serde(rename = "type")]
     kind: String,
     text: String,
-    annotations: Vec<serde_json::Value>,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -243,6 +638,24 @@ pub enum OpenaiStreamResponse {
         response: OpenaiResponse,
         sequence_number: u64,
     },
+    #[serde(rename = "response.incomplete")]
+    ResponseIncomplete {
+        response: OpenaiResponse,
+        sequence_number: u64,
+    },
+    #[serde(rename = "response.failed")]
+    ResponseFailed {
+        response: OpenaiResponse,
+        sequence_number: u64,
+    },
+    /// An error that ended the stream
+    #[serde(rename = "error")]
+    Error {
+        code: Option<String>,
+        message: String,
+        param: Option<String>,
+        sequence_number: u64,
+    },
     #[serde(rename = "response.output_item.added")]
     OutputItemAdded {
         response_id: Option<String>,
@@ -310,6 +723,48 @@ pub enum OpenaiStreamResponse {
         text: String,
         sequence_number: u64,
     },
+    #[serde(rename = "response.reasoning_summary_text.delta")]
+    ReasoningSummaryTextDelta {
+        item_id: String,
+        output_index: u64,
+        summary_index: u64,
+        delta: String,
+        sequence_number: u64,
+    },
+    #[serde(rename = "response.reasoning_summary_text.done")]
+    ReasoningSummaryTextDone {
+        item_id: String,
+        output_index: u64,
+        summary_index: u64,
+        text: String,
+        sequence_number: u64,
+    },
+    /// Any informational event that is not handled by this crate.
+    #[serde(other)]
+    Other,
+}
+
+impl OpenaiStreamResponse {
+    /// Returns the event, or an error if it reports that the response failed.
+    pub(crate) fn error_for_event(self) -> Result<Self> {
+        let error = match self {
+            Self::ResponseFailed { response, .. } => response
+                .error_message()
+                .unwrap_or_else(|| format!("Response {} failed", response.id())),
+            Self::Error {
+                code: Some(code),
+                message,
+                ..
+            } => format!("{code}: {message}"),
+            Self::Error { message, .. } => message,
+            event => return Ok(event),
+        };
+
+        Err(Error::ProviderError {
+            provider: "OpenAI".into(),
+            error,
+        })
+    }
 }
 
 impl From<OpenaiStreamResponse> for AiResponse {
@@ -317,26 +772,64 @@ impl From<OpenaiStreamResponse> for AiResponse {
         match response {
             OpenaiStreamResponse::TextDelta { delta, .. }
             | OpenaiStreamResponse::OutputTextDelta { delta, .. } => Self {
+                candidates: vec![Candidate {
+                    text: delta.clone(),
+                    ..Default::default()
+                }],
                 text: delta,
-                token_usage: TokenUsage::default(),
-            },
-            OpenaiStreamResponse::ResponseCompleted { response, .. } => Self {
-                text: "".to_string(),
-                token_usage: TokenUsage {
-                    input_tokens: response.usage.as_ref().map(|usage| usage.input_tokens),
-                    output_tokens: response.usage.as_ref().map(|usage| usage.output_tokens),
-                    total_tokens: response.usage.as_ref().map(|usage| usage.total_tokens),
-                },
+                ..Default::default()
             },
-            _ => Self {
-                text: String::new(),
-                token_usage: TokenUsage::default(),
+            OpenaiStreamResponse::ReasoningSummaryTextDelta { delta, .. } => Self {
+                reasoning: Some(delta),
+                ..Default::default()
             },
+            OpenaiStreamResponse::ResponseCompleted { response, .. }
+            | OpenaiStreamResponse::ResponseIncomplete { response, .. } => {
+                let finish_reason = response.finish_reason();
+
+                Self {
+                    token_usage: response.usage.as_ref().map(Into::into).unwrap_or_default(),
+                    candidates: vec![Candidate {
+                        finish_reason: finish_reason.clone(),
+                        ..Default::default()
+                    }],
+                    finish_reason,
+                    ..Default::default()
+                }
+            }
+            _ => Self::default(),
         }
     }
 }
 
+/// Metadata of a model available through the OpenAI API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct OpenaiModelInfo {
+    /// The id of the model as used in requests
+    pub id: String,
+    /// The Unix timestamp, in seconds, of when the model was created
+    pub created: u64,
+    /// The organization that owns the model
+    pub owned_by: String,
+}
+
+impl OpenaiModelInfo {
+    /// Returns the model, as a known variant or as [`OpenaiModel::Custom`].
+    #[must_use]
+    pub fn model(&self) -> OpenaiModel {
+        OpenaiModel::try_from(self.id.as_str())
+            .unwrap_or_else(|_| OpenaiModel::Custom(self.id.clone()))
+    }
+}
+
+/// The list of available models.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct OpenaiModelsList {
+    pub(crate) data: Vec<OpenaiModelInfo>,
+}
+
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -344,7 +837,6 @@ mod tests {
     fn test_extract_text() {
         let response = OpenaiResponse {
             output: vec![Output::Content {
-                kind: "content".to_string(),
                 id: "id1".to_string(),
                 status: None,
                 role: None,
@@ -354,7 +846,7 @@ mod tests {
                         ..Default::default()
                     },
                     Content {
-                        text: "World".to_string(),
+                        text: " World".to_string(),
                         ..Default::default()
                     },
                 ],
@@ -370,4 +862,208 @@ mod tests {
         let test_response = OpenaiResponse::default();
         assert_eq!(test_response.extract_text(), "");
     }
+
+    #[test]
+    fn test_extract_reasoning() {
+        let response = OpenaiResponse {
+            output: vec![
+                Output::Summary {
+                    id: "rs_1".to_string(),
+                    summary: vec![
+                        SummaryText {
+                            kind: "summary_text".to_string(),
+                            text: "First thought.".to_string(),
+                        },
+                        SummaryText {
+                            kind: "summary_text".to_string(),
+                            text: "Second thought.".to_string(),
+                        },
+                    ],
+                },
+                Output::Content {
+                    id: "msg_1".to_string(),
+                    status: None,
+                    role: None,
+                    content: vec![Content {
+                        text: "Answer".to_string(),
+                        ..Default::default()
+                    }],
+                },
+            ],
+            usage: Some(Usage {
+                input_tokens_details: Some(InputTokensDetails { cached_tokens: 8 }),
+                output_tokens_details: Some(OutputTokensDetails {
+                    reasoning_tokens: 64,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            response.extract_reasoning().as_deref(),
+            Some("First thought.\n\nSecond thought.")
+        );
+
+        let response = AiResponse::from(response);
+        assert_eq!(response.text, "Answer");
+        assert_eq!(response.candidates.len(), 1);
+        assert_eq!(response.candidates[0].text, "Answer");
+        assert_eq!(response.token_usage.reasoning_tokens, Some(64));
+        assert_eq!(response.token_usage.cached_input_tokens, Some(8));
+    }
+
+    #[test]
+    fn test_stream_reasoning_delta() {
+        let event: OpenaiStreamResponse = serde_json::from_value(serde_json::json!({
+            "type": "response.reasoning_summary_text.delta",
+            "item_id": "rs_1",
+            "output_index": 0,
+            "summary_index": 0,
+            "delta": "Thinking",
+            "sequence_number": 4
+        }))
+        .unwrap();
+
+        let response = AiResponse::from(event);
+        assert_eq!(response.text, "");
+        assert_eq!(response.reasoning.as_deref(), Some("Thinking"));
+    }
+
+    #[test]
+    fn test_finish_reason() {
+        let response = OpenaiResponse {
+            status: "incomplete".to_string(),
+            incomplete_details: Some(IncompleteDetails {
+                reason: "max_output_tokens".to_string(),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(response.finish_reason(), Some(FinishReason::Length));
+
+        let response = OpenaiResponse {
+            status: "completed".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            AiResponse::from(response).finish_reason,
+            Some(FinishReason::Stop)
+        );
+
+        let response = OpenaiResponse {
+            status: "in_progress".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(response.finish_reason(), None);
+    }
+
+    #[test]
+    fn test_stream_unknown_event() {
+        let event: OpenaiStreamResponse = serde_json::from_value(serde_json::json!({
+            "type": "response.reasoning_summary_part.added",
+            "item_id": "rs_1",
+            "output_index": 0,
+            "summary_index": 0,
+            "part": {"type": "summary_text", "text": ""},
+            "sequence_number": 3
+        }))
+        .unwrap();
+
+        assert_eq!(event, OpenaiStreamResponse::Other);
+    }
+
+    #[test]
+    fn test_deserialize_tool_output() {
+        let output: Vec<Output> = serde_json::from_value(serde_json::json!([
+            {
+                "type": "web_search_call",
+                "id": "ws_1",
+                "status": "completed",
+                "action": {"type": "search", "query": "rust release"}
+            },
+            {
+                "type": "file_search_call",
+                "id": "fs_1",
+                "status": "completed",
+                "queries": ["latchlm"],
+                "results": null
+            },
+            {
+                "type": "image_generation_call",
+                "id": "ig_1",
+                "status": "completed"
+            },
+            {
+                "type": "message",
+                "id": "msg_1",
+                "status": "completed",
+                "role": "assistant",
+                "content": [
+                    {
+                        "type": "output_text",
+                        "text": "Rust 1.90 is out.",
+                        "annotations": [
+                            {
+                                "type": "url_citation",
+                                "url": "https://blog.rust-lang.org/",
+                                "title": "Rust Blog",
+                                "start_index": 0,
+                                "end_index": 17
+                            },
+                            {"type": "file_citation", "file_id": "file_1", "index": 17},
+                            {"type": "unknown_citation"}
+                        ]
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            output[0],
+            Output::WebSearchCall(WebSearchCall {
+                id: "ws_1".to_string(),
+                status: "completed".to_string(),
+                action: Some(WebSearchAction::Search {
+                    query: Some("rust release".to_string())
+                }),
+            })
+        );
+        assert!(matches!(
+            &output[1],
+            Output::FileSearchCall(FileSearchCall { queries, results: None, .. }) if queries == &["latchlm"]
+        ));
+        assert_eq!(output[2], Output::Other);
+
+        let response = OpenaiResponse {
+            output,
+            ..Default::default()
+        };
+        assert_eq!(response.extract_text(), "Rust 1.90 is out.");
+        assert_eq!(
+            response.annotations(),
+            [
+                &Annotation::UrlCitation {
+                    url: "https://blog.rust-lang.org/".to_string(),
+                    title: "Rust Blog".to_string(),
+                    start_index: 0,
+                    end_index: 17,
+                },
+                &Annotation::FileCitation {
+                    file_id: "file_1".to_string(),
+                    filename: None,
+                    index: 17,
+                },
+                &Annotation::Other,
+            ]
+        );
+        assert_eq!(
+            response.citations(),
+            [Citation {
+                url: "https://blog.rust-lang.org/".to_string(),
+                title: Some("Rust Blog".to_string()),
+                text: Some("Rust 1.90 is out.".to_string()),
+            }]
+        );
+    }
 }
@@ -0,0 +1,244 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+
+use futures::StreamExt;
+use latchlm_core::{AiProvider, AiRequest, CallbackCredential, CredentialProvider, Error};
+use latchlm_openai::{AzureOpenai, OpenaiModel};
+use secrecy::SecretString;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{bearer_token, body_partial_json, header, method, path, query_param},
+};
+
+fn response_body(text: &str) -> serde_json::Value {
+    serde_json::json!({
+      "id": "resp_1",
+      "object": "response",
+      "created_at": 1741476542,
+      "status": "completed",
+      "model": "gpt-4o",
+      "output": [
+        {
+          "type": "message",
+          "id": "msg_1",
+          "status": "completed",
+          "role": "assistant",
+          "content": [{"type": "output_text", "text": text, "annotations": []}]
+        }
+      ],
+      "parallel_tool_calls": true,
+      "store": true,
+      "temperature": 1.0,
+      "text": {"format": {"type": "text"}},
+      "tool_choice": "auto",
+      "tools": [],
+      "top_p": 1.0,
+      "truncation": "disabled",
+      "metadata": {}
+    })
+}
+
+#[tokio::test]
+async fn test_azure_request_with_api_key() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/openai/responses"))
+        .and(query_param("api-version", AzureOpenai::DEFAULT_API_VERSION))
+        .and(header("api-key", "test_api_key"))
+        .and(body_partial_json(
+            serde_json::json!({"model": "prod-gpt4o"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body("Hello")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .endpoint(mock_server.uri().parse().expect("Failed to parse URL"))
+        .api_key(SecretString::from("test_api_key"))
+        .deployment(&OpenaiModel::Gpt4o, "prod-gpt4o")
+        .build()
+        .expect("Failed to build client");
+
+    let response = client
+        .send_request(&OpenaiModel::Gpt4o, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hello");
+}
+
+#[tokio::test]
+async fn test_azure_request_with_entra_token() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/gateway/openai/responses"))
+        .and(query_param("api-version", "2025-03-01-preview"))
+        .and(bearer_token("entra-token"))
+        .and(body_partial_json(serde_json::json!({"model": "gpt-4.1"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body("Hello")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .endpoint(
+            format!("{}/gateway", mock_server.uri())
+                .parse()
+                .expect("Failed to parse URL"),
+        )
+        .entra_token(SecretString::from("entra-token"))
+        .api_version("2025-03-01-preview")
+        .build()
+        .expect("Failed to build client");
+
+    client
+        .send_request(&OpenaiModel::Gpt41, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+}
+
+#[tokio::test]
+async fn test_azure_streaming_request() {
+    let mock_server = MockServer::start().await;
+
+    let body = ["Hel", "lo"]
+        .iter()
+        .zip(0..)
+        .map(|(delta, sequence_number)| {
+            let event = serde_json::json!({
+                "type": "response.output_text.delta",
+                "item_id": "msg_1",
+                "output_index": 0,
+                "content_index": 0,
+                "delta": delta,
+                "sequence_number": sequence_number
+            });
+
+            format!("event: response.output_text.delta\ndata: {event}\n\n")
+        })
+        .collect::<String>();
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/openai/responses"))
+        .and(body_partial_json(serde_json::json!({"stream": true})))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .endpoint(mock_server.uri().parse().expect("Failed to parse URL"))
+        .api_key(SecretString::from("test_api_key"))
+        .build()
+        .expect("Failed to build client");
+
+    let text = client
+        .send_streaming(&OpenaiModel::Gpt4o, AiRequest::default())
+        .map(|chunk| chunk.expect("Failed to receive chunk").text)
+        .collect::<String>()
+        .await;
+
+    assert_eq!(text, "Hello");
+}
+
+#[tokio::test]
+async fn test_azure_api_error() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("DeploymentNotFound"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let client = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .endpoint(mock_server.uri().parse().expect("Failed to parse URL"))
+        .api_key(SecretString::from("test_api_key"))
+        .build()
+        .expect("Failed to build client");
+
+    let result = client
+        .send_request(&OpenaiModel::Gpt4o, AiRequest::default())
+        .await;
+
+    assert!(matches!(result, Err(Error::ApiError { status: 404, .. })));
+}
+
+#[test]
+fn test_azure_builder_requires_endpoint() {
+    let result = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .build();
+
+    assert!(matches!(result, Err(Error::ProviderError { .. })));
+}
+
+#[test]
+fn test_azure_builder_rejects_invalid_header_value() {
+    let result = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .endpoint("https://example.openai.azure.com".parse().unwrap())
+        .api_key(SecretString::from("test_api_key\n"))
+        .build();
+
+    assert!(matches!(result, Err(Error::CredentialError(_))));
+}
+
+#[tokio::test]
+async fn test_azure_refreshes_expired_entra_token() {
+    let mock_server = MockServer::start().await;
+
+    let _expired_guard = Mock::given(method("POST"))
+        .and(bearer_token("entra-token-0"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Token expired"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _refreshed_guard = Mock::given(method("POST"))
+        .and(bearer_token("entra-token-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response_body("Hello")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tokens: Arc<dyn CredentialProvider> = Arc::new(CallbackCredential::new({
+        let calls = Arc::clone(&calls);
+        move || {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(SecretString::from(format!("entra-token-{call}"))) }
+        }
+    }));
+
+    let client = AzureOpenai::builder()
+        .client(reqwest::Client::new())
+        .endpoint(mock_server.uri().parse().expect("Failed to parse URL"))
+        .entra_credentials(tokens)
+        .build()
+        .expect("Failed to build client");
+
+    let response = client
+        .send_request(&OpenaiModel::Gpt4o, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hello");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -5,12 +5,17 @@
 #![allow(clippy::unwrap_used)]
 #![allow(clippy::expect_used)]
 
-use latchlm_core::{AiModel, AiProvider, AiRequest, Error, ModelId};
-use latchlm_openai::{Openai, OpenaiModel};
+use futures::StreamExt;
+use latchlm_core::{AiModel, AiProvider, AiRequest, Error, ModelId, StaticCredential};
+use latchlm_openai::{
+    Annotation, BatchStatus, Openai, OpenaiModel, OpenaiRequestOptions, OpenaiTool, Output,
+    PollOptions, ResponseStatus, Truncation, WebSearchAction,
+};
+use latchlm_test_support::exact_text;
 use secrecy::{ExposeSecret, SecretString};
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{bearer_token, method},
+    matchers::{bearer_token, body_partial_json, body_string_contains, header_regex, method, path},
 };
 
 #[tokio::test]
@@ -94,7 +99,8 @@ async fn test_openai_request_response() {
         reqwest::Client::new(),
         mock_base_url.parse().expect("Failed to parse URL"),
         test_api_key,
-    );
+    )
+    .expect("Failed to create client");
 
     // Make the request
     let response = test_client
@@ -102,6 +108,7 @@ async fn test_openai_request_response() {
             &model,
             AiRequest {
                 text: "Test Message".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -203,13 +210,15 @@ async fn test_openai_gpt5_nano_response_format() {
         reqwest::Client::new(),
         mock_base_url.parse().expect("Failed to parse URL"),
         test_api_key,
-    );
+    )
+    .expect("Failed to create client");
 
     let response = client
         .send_request(
             &model,
             AiRequest {
                 text: "What is AI?".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -241,7 +250,8 @@ async fn test_openai_error_unhautenticated() {
         reqwest::Client::new(),
         mock_server_url.parse().expect("Failed to parse URL"),
         api_key,
-    );
+    )
+    .expect("Failed to create client");
 
     let _mock_guard = Mock::given(method("POST"))
         .respond_with(ResponseTemplate::new(401).set_body_json(&error_response_body))
@@ -254,6 +264,7 @@ async fn test_openai_error_unhautenticated() {
             &model,
             AiRequest {
                 text: "test".into(),
+                ..Default::default()
             },
         )
         .await;
@@ -280,6 +291,7 @@ async fn test_openai_error_invalid_model() {
             ModelId {
                 id: self.as_ref().into(),
                 name: self.as_ref().into(),
+                ..Default::default()
             }
         }
     }
@@ -288,10 +300,12 @@ async fn test_openai_error_invalid_model() {
         reqwest::Client::new(),
         "http://test.test".parse().expect("Failed to parse URL"),
         SecretString::from("api-key"),
-    );
+    )
+    .expect("Failed to create client");
 
     let request = AiRequest {
         text: "Test".to_string(),
+        ..Default::default()
     };
 
     let err = openai
@@ -306,3 +320,902 @@ async fn test_openai_error_invalid_model() {
         _ => panic!("Expected InvalidModelError"),
     }
 }
+
+#[tokio::test]
+async fn test_openai_preserves_exact_text() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = mock_server.uri();
+
+    let content = exact_text::PARTS
+        .iter()
+        .map(|text| serde_json::json!({"type": "output_text", "text": text, "annotations": []}))
+        .collect::<Vec<_>>();
+
+    let mock_response_body = serde_json::json!({
+      "id": "resp_1",
+      "object": "response",
+      "created_at": 1741476542,
+      "status": "completed",
+      "model": "gpt-4.1-nano",
+      "output": [
+        {"type": "message", "id": "msg_1", "status": "completed", "role": "assistant", "content": content}
+      ],
+      "parallel_tool_calls": true,
+      "store": true,
+      "temperature": 1.0,
+      "text": {"format": {"type": "text"}},
+      "tool_choice": "auto",
+      "tools": [],
+      "top_p": 1.0,
+      "truncation": "disabled",
+      "metadata": {}
+    });
+
+    let _mock_guard = Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_response_body))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url.parse().expect("Failed to parse URL"),
+        SecretString::from("test_api_key"),
+    )
+    .expect("Failed to create client");
+
+    let response = test_client
+        .send_request(&OpenaiModel::Gpt41Nano, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+
+    exact_text::assert_preserved(&response.text);
+}
+
+#[tokio::test]
+async fn test_openai_streaming_preserves_exact_text() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = mock_server.uri();
+
+    let body = exact_text::PARTS
+        .iter()
+        .zip(0..)
+        .map(|(delta, sequence_number)| {
+            let event = serde_json::json!({
+                "type": "response.output_text.delta",
+                "item_id": "msg_1",
+                "output_index": 0,
+                "content_index": 0,
+                "delta": delta,
+                "sequence_number": sequence_number
+            });
+
+            format!("event: response.output_text.delta\ndata: {event}\n\n")
+        })
+        .collect::<String>();
+
+    let _mock_guard = Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url.parse().expect("Failed to parse URL"),
+        SecretString::from("test_api_key"),
+    )
+    .expect("Failed to create client");
+
+    let text = test_client
+        .send_streaming(&OpenaiModel::Gpt41Nano, AiRequest::default())
+        .map(|chunk| chunk.expect("Failed to receive chunk").text)
+        .collect::<String>()
+        .await;
+
+    exact_text::assert_preserved(&text);
+}
+
+#[tokio::test]
+async fn test_openai_streaming_error_events() {
+    let mock_server = MockServer::start().await;
+
+    let delta = serde_json::json!({
+        "type": "response.output_text.delta",
+        "item_id": "msg_1",
+        "output_index": 0,
+        "content_index": 0,
+        "delta": "Hel",
+        "sequence_number": 1
+    });
+    let events = [
+        (
+            "error",
+            serde_json::json!({
+                "type": "error",
+                "code": "server_error",
+                "message": "The server had an error",
+                "param": null,
+                "sequence_number": 2
+            }),
+        ),
+        (
+            "response.failed",
+            serde_json::json!({
+                "type": "response.failed",
+                "response": stored_response("resp_2", "failed"),
+                "sequence_number": 2
+            }),
+        ),
+    ];
+
+    let test_client = Openai::new_with_base_url(
+        reqwest::Client::new(),
+        mock_server.uri().parse().expect("Failed to parse URL"),
+        SecretString::from("test_api_key"),
+    )
+    .expect("Failed to create client");
+
+    for (kind, event) in events {
+        let body = format!(
+            "event: response.output_text.delta\ndata: {delta}\n\nevent: {kind}\ndata: {event}\n\n"
+        );
+
+        let _mock_guard = Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .expect(1)
+            .mount_as_scoped(&mock_server)
+            .await;
+
+        let chunks = test_client
+            .send_streaming(&OpenaiModel::Gpt41Nano, AiRequest::default())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].as_ref().unwrap().text, "Hel");
+        assert!(matches!(&chunks[1], Err(Error::ProviderError { .. })));
+    }
+}
+
+#[tokio::test]
+async fn test_openai_models_endpoint() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = format!("{}/v1", mock_server.uri());
+
+    let test_api_key = SecretString::from("test_api_key");
+
+    let _mock_guard = Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .and(bearer_token(test_api_key.expose_secret()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "gpt-4o", "object": "model", "created": 1715367049, "owned_by": "system"},
+                {"id": "gpt-6", "object": "model", "created": 1790000000, "owned_by": "system"}
+            ]
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url.parse().expect("Failed to parse URL"),
+        test_api_key,
+    )
+    .expect("Failed to create client");
+
+    let models = test_client.models().await.expect("Failed to list models");
+
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].model(), OpenaiModel::Gpt4o);
+    assert_eq!(models[1].model(), OpenaiModel::Custom("gpt-6".to_owned()));
+    assert_eq!(models[1].owned_by, "system");
+}
+
+#[tokio::test]
+async fn test_openai_custom_model_request() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = mock_server.uri();
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({"model": "gpt-6"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "id": "resp_1",
+          "object": "response",
+          "created_at": 1741476542,
+          "status": "completed",
+          "model": "gpt-6",
+          "output": [],
+          "parallel_tool_calls": true,
+          "store": true,
+          "temperature": 1.0,
+          "text": {"format": {"type": "text"}},
+          "tool_choice": "auto",
+          "tools": [],
+          "top_p": 1.0,
+          "truncation": "disabled",
+          "metadata": {}
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url.parse().expect("Failed to parse URL"),
+        SecretString::from("test_api_key"),
+    )
+    .expect("Failed to create client");
+
+    let model = OpenaiModel::Custom("gpt-6".to_owned());
+    test_client
+        .send_request(&model, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+}
+
+#[tokio::test]
+async fn test_openai_base_url_keeps_path_prefix() {
+    let mock_server = MockServer::start().await;
+    let base_url = reqwest::Url::parse(&format!("{}/proxy/openai/v1", mock_server.uri()))
+        .expect("Failed to parse URL");
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/proxy/openai/v1/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "id": "resp_1",
+          "object": "response",
+          "created_at": 1741476542,
+          "status": "completed",
+          "model": "gpt-5-mini",
+          "output": [],
+          "parallel_tool_calls": true,
+          "store": true,
+          "temperature": 1.0,
+          "text": {"format": {"type": "text"}},
+          "tool_choice": "auto",
+          "tools": [],
+          "top_p": 1.0,
+          "truncation": "disabled",
+          "metadata": {}
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(base_url)
+        .build()
+        .expect("Failed to build client");
+
+    test_client
+        .send_request(&OpenaiModel::Gpt5Mini, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+}
+
+#[tokio::test]
+async fn test_openai_sends_provided_credentials() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(bearer_token("provided_api_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "id": "resp_1",
+          "object": "response",
+          "created_at": 1741476542,
+          "status": "completed",
+          "model": "gpt-5-mini",
+          "output": [],
+          "parallel_tool_calls": true,
+          "store": true,
+          "temperature": 1.0,
+          "text": {"format": {"type": "text"}},
+          "tool_choice": "auto",
+          "tools": [],
+          "top_p": 1.0,
+          "truncation": "disabled",
+          "metadata": {}
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .credentials(StaticCredential::new(SecretString::from(
+            "provided_api_key",
+        )))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    test_client
+        .send_request(&OpenaiModel::Gpt5Mini, AiRequest::default())
+        .await
+        .expect("Failed to send request");
+}
+
+fn stored_response(id: &str, status: &str) -> serde_json::Value {
+    serde_json::json!({
+      "id": id,
+      "object": "response",
+      "created_at": 1741476542,
+      "status": status,
+      "background": true,
+      "model": "gpt-5-mini",
+      "output": [],
+      "parallel_tool_calls": true,
+      "previous_response_id": "resp_1",
+      "store": true,
+      "temperature": 1.0,
+      "text": {"format": {"type": "text"}},
+      "tool_choice": "auto",
+      "tools": [],
+      "top_p": 1.0,
+      "truncation": "auto",
+      "metadata": {"session": "42"}
+    })
+}
+
+#[tokio::test]
+async fn test_openai_request_continues_stored_response() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(serde_json::json!({
+            "previous_response_id": "resp_1",
+            "store": true,
+            "metadata": {"session": "42"},
+            "truncation": "auto"
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(stored_response("resp_2", "completed")),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let options = OpenaiRequestOptions {
+        previous_response_id: Some("resp_1".into()),
+        store: Some(true),
+        metadata: [("session".to_owned(), "42".to_owned())].into(),
+        truncation: Some(Truncation::Auto),
+        ..Default::default()
+    };
+
+    let response = test_client
+        .request_with_options(OpenaiModel::Gpt5Mini, AiRequest::default(), options)
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.id(), "resp_2");
+    assert_eq!(response.previous_response_id(), Some("resp_1"));
+    assert!(response.store());
+    assert_eq!(response.metadata()["session"], "42");
+}
+
+#[tokio::test]
+async fn test_openai_stored_response_lifecycle() {
+    let mock_server = MockServer::start().await;
+
+    let _retrieve_guard = Mock::given(method("GET"))
+        .and(path("/responses/resp_2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(stored_response("resp_2", "in_progress")),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _cancel_guard = Mock::given(method("POST"))
+        .and(path("/responses/resp_2/cancel"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(stored_response("resp_2", "cancelled")),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _delete_guard = Mock::given(method("DELETE"))
+        .and(path("/responses/resp_2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "resp_2",
+            "object": "response.deleted",
+            "deleted": true
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _missing_guard = Mock::given(method("GET"))
+        .and(path("/responses/resp_missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Response not found"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _encoded_guard = Mock::given(method("GET"))
+        .and(path("/responses/..%2Fmodels%3Flimit=1"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Response not found"))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let response = test_client
+        .retrieve_response("resp_2")
+        .await
+        .expect("Failed to retrieve response");
+    assert_eq!(response.status(), ResponseStatus::InProgress);
+    assert_eq!(response.finish_reason(), None);
+
+    let response = test_client
+        .cancel_response("resp_2")
+        .await
+        .expect("Failed to cancel response");
+    assert_eq!(response.status(), ResponseStatus::Cancelled);
+
+    test_client
+        .delete_response("resp_2")
+        .await
+        .expect("Failed to delete response");
+
+    let result = test_client.retrieve_response("resp_missing").await;
+    assert!(matches!(result, Err(Error::ApiError { status: 404, .. })));
+
+    let result = test_client.retrieve_response("../models?limit=1").await;
+    assert!(matches!(result, Err(Error::ApiError { status: 404, .. })));
+}
+
+#[tokio::test]
+async fn test_openai_request_in_background() {
+    let mock_server = MockServer::start().await;
+
+    let _submit_guard = Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(
+            serde_json::json!({"background": true, "store": true}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(stored_response("resp_bg", "queued")),
+        )
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _in_progress_guard = Mock::given(method("GET"))
+        .and(path("/responses/resp_bg"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(stored_response("resp_bg", "in_progress")),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let mut completed = stored_response("resp_bg", "completed");
+    completed["output"] = serde_json::json!([{
+        "type": "message",
+        "id": "msg_1",
+        "status": "completed",
+        "role": "assistant",
+        "content": [{"type": "output_text", "text": "Proof complete", "annotations": []}]
+    }]);
+
+    let _completed_guard = Mock::given(method("GET"))
+        .and(path("/responses/resp_bg"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completed))
+        .with_priority(2)
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let poll = PollOptions {
+        interval: std::time::Duration::from_millis(1),
+        ..Default::default()
+    };
+
+    let response = test_client
+        .request_in_background(
+            OpenaiModel::Gpto3Pro,
+            AiRequest::default(),
+            OpenaiRequestOptions::default(),
+            poll,
+        )
+        .await
+        .expect("Failed to run background request");
+
+    assert_eq!(response.status(), ResponseStatus::Completed);
+    assert!(response.background());
+    assert_eq!(response.extract_text(), "Proof complete");
+}
+
+#[tokio::test]
+async fn test_openai_request_in_background_fails_on_submit() {
+    let mock_server = MockServer::start().await;
+
+    let mut failed = stored_response("resp_bg", "failed");
+    failed["error"] = serde_json::json!({"code": "server_error", "message": "The model crashed"});
+
+    let _submit_guard = Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(failed))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let result = test_client
+        .request_in_background(
+            OpenaiModel::Gpto3Pro,
+            AiRequest::default(),
+            OpenaiRequestOptions::default(),
+            PollOptions::default(),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(Error::ProviderError { error, .. }) if error == "The model crashed")
+    );
+}
+
+#[tokio::test]
+async fn test_openai_wait_for_failed_or_slow_response() {
+    let mock_server = MockServer::start().await;
+
+    let mut failed = stored_response("resp_failed", "failed");
+    failed["error"] = serde_json::json!({"code": "server_error", "message": "The model crashed"});
+
+    let _failed_guard = Mock::given(method("GET"))
+        .and(path("/responses/resp_failed"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(failed))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _slow_guard = Mock::given(method("GET"))
+        .and(path("/responses/resp_slow"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(stored_response("resp_slow", "in_progress")),
+        )
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let poll = PollOptions {
+        interval: std::time::Duration::from_millis(5),
+        timeout: Some(std::time::Duration::from_millis(20)),
+    };
+
+    let result = test_client.wait_for_response("resp_failed", poll).await;
+    assert!(
+        matches!(result, Err(Error::ProviderError { error, .. }) if error == "The model crashed")
+    );
+
+    let result = test_client.wait_for_response("resp_slow", poll).await;
+    assert!(
+        matches!(result, Err(Error::ProviderError { error, .. }) if error.contains("Timed out"))
+    );
+}
+
+#[tokio::test]
+async fn test_openai_web_search_tool() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(serde_json::json!({
+            "tools": [{"type": "web_search"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "id": "resp_1",
+          "object": "response",
+          "created_at": 1741476542,
+          "status": "completed",
+          "model": "gpt-4.1",
+          "output": [
+            {
+              "type": "web_search_call",
+              "id": "ws_1",
+              "status": "completed",
+              "action": {"type": "search", "query": "latest rust release"}
+            },
+            {
+              "type": "message",
+              "id": "msg_1",
+              "status": "completed",
+              "role": "assistant",
+              "content": [
+                {
+                  "type": "output_text",
+                  "text": "Rust 1.90 was released.",
+                  "annotations": [
+                    {
+                      "type": "url_citation",
+                      "url": "https://blog.rust-lang.org/",
+                      "title": "Rust Blog",
+                      "start_index": 0,
+                      "end_index": 23
+                    }
+                  ]
+                }
+              ]
+            }
+          ],
+          "parallel_tool_calls": true,
+          "store": true,
+          "temperature": 1.0,
+          "text": {"format": {"type": "text"}},
+          "tool_choice": "auto",
+          "tools": [{"type": "web_search", "search_context_size": "medium"}],
+          "top_p": 1.0,
+          "truncation": "disabled",
+          "metadata": {}
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let options = OpenaiRequestOptions {
+        tools: vec![OpenaiTool::web_search()],
+        ..Default::default()
+    };
+
+    let response = test_client
+        .request_with_options(OpenaiModel::Gpt41, AiRequest::default(), options)
+        .await
+        .expect("Failed to send request");
+
+    let Output::WebSearchCall(call) = &response.output()[0] else {
+        panic!("Expected a web search call");
+    };
+    assert_eq!(
+        call.action,
+        Some(WebSearchAction::Search {
+            query: Some("latest rust release".into())
+        })
+    );
+
+    assert_eq!(response.extract_text(), "Rust 1.90 was released.");
+    assert!(matches!(
+        response.annotations()[..],
+        [Annotation::UrlCitation { url, .. }] if url == "https://blog.rust-lang.org/"
+    ));
+}
+
+fn batch(status: &str) -> serde_json::Value {
+    serde_json::json!({
+      "id": "batch_1",
+      "object": "batch",
+      "endpoint": "/v1/responses",
+      "errors": null,
+      "input_file_id": "file-input",
+      "completion_window": "24h",
+      "status": status,
+      "output_file_id": if status == "completed" { Some("file-output") } else { None },
+      "error_file_id": if status == "completed" { Some("file-errors") } else { None },
+      "created_at": 1741476542,
+      "completed_at": null,
+      "expires_at": 1741562942,
+      "request_counts": {"total": 2, "completed": 1, "failed": 1},
+      "metadata": null
+    })
+}
+
+#[tokio::test]
+async fn test_openai_batch_lifecycle() {
+    let mock_server = MockServer::start().await;
+
+    let _upload_guard = Mock::given(method("POST"))
+        .and(path("/files"))
+        .and(header_regex("content-type", "^multipart/form-data"))
+        .and(body_string_contains("\"custom_id\":\"request-1\""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-input",
+            "object": "file",
+            "bytes": 512,
+            "created_at": 1741476542,
+            "expires_at": null,
+            "filename": "batch.jsonl",
+            "purpose": "batch"
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _create_guard = Mock::given(method("POST"))
+        .and(path("/batches"))
+        .and(body_partial_json(serde_json::json!({
+            "input_file_id": "file-input",
+            "endpoint": "/v1/responses",
+            "completion_window": "24h"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(batch("validating")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _in_progress_guard = Mock::given(method("GET"))
+        .and(path("/batches/batch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(batch("in_progress")))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _completed_guard = Mock::given(method("GET"))
+        .and(path("/batches/batch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(batch("completed")))
+        .with_priority(2)
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let mut completed = stored_response("resp_batch", "completed");
+    completed["output"] = serde_json::json!([{
+        "type": "message",
+        "id": "msg_1",
+        "status": "completed",
+        "role": "assistant",
+        "content": [{"type": "output_text", "text": "First answer", "annotations": []}]
+    }]);
+    let output = serde_json::json!({
+        "id": "batch_req_1",
+        "custom_id": "request-0",
+        "response": {"status_code": 200, "request_id": "req_1", "body": completed},
+        "error": null
+    });
+    let errors = serde_json::json!({
+        "id": "batch_req_2",
+        "custom_id": "request-1",
+        "response": {
+            "status_code": 400,
+            "request_id": "req_2",
+            "body": {"error": {"message": "Invalid input"}}
+        },
+        "error": null
+    });
+
+    let _output_guard = Mock::given(method("GET"))
+        .and(path("/files/file-output/content"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("{output}\n")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _errors_guard = Mock::given(method("GET"))
+        .and(path("/files/file-errors/content"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("{errors}\n")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let _cancel_guard = Mock::given(method("POST"))
+        .and(path("/batches/batch_1/cancel"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(batch("cancelling")))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openai::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test_api_key"))
+        .base_url(reqwest::Url::parse(&mock_server.uri()).expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let request = |text: &str| AiRequest {
+        text: text.into(),
+        ..Default::default()
+    };
+    let submitted = test_client
+        .submit_batch([
+            (OpenaiModel::Gpt5Mini, request("First")),
+            (OpenaiModel::Gpt5Mini, request("Second")),
+        ])
+        .await
+        .expect("Failed to submit batch");
+    assert_eq!(submitted.status(), BatchStatus::Validating);
+
+    let poll = PollOptions {
+        interval: std::time::Duration::from_millis(1),
+        ..Default::default()
+    };
+    let finished = test_client
+        .wait_for_batch(&submitted.id, poll)
+        .await
+        .expect("Failed to wait for batch");
+    assert_eq!(finished.status(), BatchStatus::Completed);
+    assert_eq!(finished.request_counts.unwrap().failed, 1);
+
+    let results = test_client
+        .batch_results(&finished)
+        .await
+        .expect("Failed to download batch results");
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results["request-0"].as_ref().unwrap().extract_text(),
+        "First answer"
+    );
+    assert!(matches!(
+        &results["request-1"],
+        Err(Error::ApiError { status: 400, message }) if message.contains("Invalid input")
+    ));
+
+    let cancelled = test_client
+        .cancel_batch(&submitted.id)
+        .await
+        .expect("Failed to cancel batch");
+    assert_eq!(cancelled.status(), BatchStatus::Cancelling);
+}
+
+#[test]
+fn test_openai_rejects_responses_endpoint_base_url() {
+    for base_url in [
+        "https://api.openai.com/v1/responses",
+        "https://api.openai.com/v1/responses/",
+    ] {
+        let result = Openai::builder()
+            .client(reqwest::Client::new())
+            .api_key(SecretString::from("test_api_key"))
+            .base_url(base_url.parse().expect("Failed to parse URL"))
+            .build();
+
+        assert!(matches!(result, Err(Error::InvalidUrlError(_))));
+
+        let result = Openai::new_with_base_url(
+            reqwest::Client::new(),
+            base_url.parse().expect("Failed to parse URL"),
+            SecretString::from("test_api_key"),
+        );
+        assert!(matches!(result, Err(Error::InvalidUrlError(_))));
+    }
+}
@@ -6,13 +6,18 @@
 //!
 //! This crate implements a client for interacting with the OpenRouter API.
 
-use eventsource_stream::Eventsource;
 use futures::{FutureExt, StreamExt, stream::BoxStream};
-use latchlm_core::{AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, Error, ModelId, Result};
+use latchlm_core::{
+    AiModel, AiProvider, AiRequest, AiResponse, BoxFuture, CredentialProvider, Error, ModelId,
+    Result, StaticCredential, join_url, parse_event_stream_until, parse_json, send_authenticated,
+};
 use reqwest::{Client, Url};
 use secrecy::{ExposeSecret, SecretString};
 use std::{borrow::Cow, env::VarError, future::ready, sync::Arc};
 
+mod request;
+use request::OpenrouterRequest;
+
 mod response;
 pub use response::*;
 
@@ -34,10 +39,20 @@ impl AiModel for OpenrouterModel {
         ModelId {
             id: Cow::Borrowed(&self.0),
             name: Cow::Borrowed(&self.0),
+            ..Default::default()
         }
     }
 }
 
+impl std::str::FromStr for OpenrouterModel {
+    type Err = Error;
+
+    /// OpenRouter serves a large and changing set of models, so any id is accepted.
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Self::new(s))
+    }
+}
+
 impl OpenrouterModel {
     pub fn new<S: Into<String>>(model_name: S) -> Self {
         Self(model_name.into())
@@ -87,9 +102,10 @@ impl std::error::Error for OpenrouterError {}
 #[derive(Debug, Clone, Default)]
 pub struct OpenrouterBuilder {
     client: Option<Client>,
-    api_key: Option<SecretString>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
     http_referer: Option<String>,
     x_title: Option<String>,
+    base_url: Option<Url>,
 }
 
 impl OpenrouterBuilder {
@@ -127,8 +143,25 @@ impl OpenrouterBuilder {
     ///
     /// The updated [`OpenrouterBuilder`] instance.
     #[must_use]
-    pub fn api_key(mut self, api_key: SecretString) -> Self {
-        self.api_key = Some(api_key);
+    pub fn api_key(self, api_key: SecretString) -> Self {
+        self.credentials(StaticCredential::new(api_key))
+    }
+
+    /// Sets the provider consulted for the API key before every request.
+    ///
+    /// Use this instead of [`OpenrouterBuilder::api_key`] to rotate keys without rebuilding
+    /// the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The updated [`OpenrouterBuilder`] instance.
+    #[must_use]
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(credentials));
         self
     }
 
@@ -136,10 +169,28 @@ impl OpenrouterBuilder {
     pub fn api_key_from_env(mut self) -> std::result::Result<Self, VarError> {
         let api_key = std::env::var("OPENROUTER_API_KEY")?;
 
-        self.api_key = Some(SecretString::from(api_key));
+        self.credentials = Some(Arc::new(StaticCredential::new(SecretString::from(api_key))));
         Ok(self)
     }
 
+    /// Sets the base URL the API paths, such as `chat/completions`, are appended to.
+    ///
+    /// Defaults to `https://openrouter.ai/api/v1/`. Any path prefix is kept, so requests can
+    /// be routed through proxies and gateways.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the API.
+    ///
+    /// # Returns
+    ///
+    /// The updated [`OpenrouterBuilder`] instance.
+    #[must_use]
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
     /// Sets the `HTTP-Referer` header to be used by the [`Openrouter`] client.
     ///
     /// # Arguments
@@ -174,17 +225,30 @@ impl OpenrouterBuilder {
     ///
     /// # Returns
     ///
-    /// The [`Openrouter`] client.
+    /// The [`Openrouter`] client, or an error if the client or API key are missing or the
+    /// base URL cannot be a base.
     pub fn build(self) -> Result<Openrouter> {
         let client = self.client.ok_or(OpenrouterError::MissingClientError)?;
-        let api_key = self.api_key.ok_or(OpenrouterError::MissingApiKeyError)?;
+        let credentials = self
+            .credentials
+            .ok_or(OpenrouterError::MissingApiKeyError)?;
+
+        let base_url = match self.base_url {
+            Some(base_url) if base_url.cannot_be_a_base() => {
+                return Err(Error::InvalidUrlError(base_url.to_string()));
+            }
+            Some(base_url) => base_url,
+            None => Url::parse(Openrouter::BASE_URL)
+                .map_err(|_| Error::InvalidUrlError(Openrouter::BASE_URL.to_owned()))?,
+        };
 
-        Ok(Openrouter::new(
+        Ok(Openrouter {
+            base_url,
             client,
-            api_key,
-            self.http_referer,
-            self.x_title,
-        ))
+            credentials,
+            http_referer: self.http_referer,
+            x_title: self.x_title,
+        })
     }
 }
 
@@ -193,7 +257,7 @@ impl OpenrouterBuilder {
 pub struct Openrouter {
     base_url: Url,
     client: Client,
-    api_key: Arc<SecretString>,
+    credentials: Arc<dyn CredentialProvider>,
     http_referer: Option<String>,
     x_title: Option<String>,
 }
@@ -224,16 +288,16 @@ impl Openrouter {
         Self {
             base_url: Url::parse(Self::BASE_URL).expect("Invalid base URL"),
             client,
-            api_key: Arc::new(api_key),
+            credentials: Arc::new(StaticCredential::new(api_key)),
             http_referer,
             x_title,
         }
     }
 
-    /// Creates a new [`Openrouter`] client with a custom base URL for testing.
+    /// Creates a new [`Openrouter`] client with a custom base URL.
     ///
-    /// This constructor is intended for testing and mocking scenarios and should **never**
-    /// be used in production code.
+    /// The API paths, such as `chat/completions`, are appended to the base URL, keeping any
+    /// path prefix it has.
     ///
     /// # Arguments
     ///
@@ -244,13 +308,12 @@ impl Openrouter {
     /// # Returns
     ///
     /// The [`Openrouter`] client.
-    #[cfg(feature = "test-utils")]
     #[must_use]
     pub fn new_with_base_url(client: Client, base_url: Url, api_key: SecretString) -> Self {
         Self {
             base_url,
             client,
-            api_key: Arc::new(api_key),
+            credentials: Arc::new(StaticCredential::new(api_key)),
             http_referer: None,
             x_title: None,
         }
@@ -310,6 +373,7 @@ impl Openrouter {
     ///         OpenrouterModel::new("openai/gpt-oss-20b"),
     ///         AiRequest {
     ///             text: "Hello".into(),
+    ///             ..Default::default()
     ///         }
     ///     ).await?;
     ///
@@ -344,48 +408,20 @@ impl Openrouter {
             headers.insert("X-Title", x_title.parse().expect("Failed to parse x-title"));
         }
 
-        let request = serde_json::json!({
-            "model": model.as_ref(),
-            "messages": [
-                {
-                    "role": "user",
-                    "content": request.text
-                }
-            ],
-        });
-
-        let url = self
-            .base_url
-            .join("chat/completions")
-            .expect("Failed to join URL");
-
-        let response = self
-            .client
-            .post(url)
-            .headers(headers)
-            .bearer_auth(self.api_key.expose_secret())
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await?;
-
-            #[cfg(feature = "tracing")]
-            tracing::error!("API error: {}", message);
-
-            return Err(Error::ApiError { status, message });
-        }
+        let request = OpenrouterRequest::new(&model, request, false);
 
-        let bytes = response.bytes().await?;
+        let url = join_url(&self.base_url, "chat/completions")?;
 
-        #[cfg(feature = "tracing")]
-        tracing::debug!("Received response: {bytes:?}");
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .headers(headers.clone())
+                .bearer_auth(api_key.expose_secret())
+                .json(&request)
+        })
+        .await?;
 
-        let response = serde_json::from_slice(&bytes)?;
-
-        Ok(response)
+        parse_json(response).await
     }
 
     /// Sends a streaming request to the OpenRouter and returns a stream of responses.
@@ -418,72 +454,21 @@ impl Openrouter {
             headers.insert("X-Title", x_title.parse().expect("Failed to parse x-title"));
         }
 
-        let request = serde_json::json!({
-            "model": model.as_ref(),
-            "messages": [
-                {
-                    "role": "user",
-                    "content": request.text
-                }
-            ],
-            "stream": true
-        });
-
-        let url = self
-            .base_url
-            .join("chat/completions")
-            .expect("Failed to join URL");
-
-        let response = self
-            .client
-            .post(url)
-            .headers(headers)
-            .bearer_auth(self.api_key.expose_secret())
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            #[cfg(feature = "tracing")]
-            tracing::error!("OpenRouter API error: {}", response.status());
-
-            return Err(Error::ApiError {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            });
-        }
+        let request = OpenrouterRequest::new(&model, request, true);
 
-        let stream = response
-            .bytes_stream()
-            .eventsource()
-            .filter_map(|result| async {
-                let event = match result {
-                    Ok(event) => {
-                        #[cfg(feature = "tracing")]
-                        tracing::debug!("OpenRouter API event: {:?}", event);
-
-                        event
-                    }
-                    Err(err) => {
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("OpenRouter error: {}", err);
-
-                        return Some(Err(Error::ProviderError {
-                            provider: "OpenRouter".to_string(),
-                            error: err.to_string(),
-                        }));
-                    }
-                };
-                let data = event.data;
-
-                if data.contains("[DONE]") {
-                    return None;
-                }
+        let url = join_url(&self.base_url, "chat/completions")?;
 
-                Some(serde_json::from_str::<OpenrouterStreamResponse>(&data).map_err(Into::into))
-            });
+        let response = send_authenticated(&*self.credentials, |api_key| {
+            self.client
+                .post(url.clone())
+                .headers(headers.clone())
+                .bearer_auth(api_key.expose_secret())
+                .json(&request)
+        })
+        .await?;
 
-        Ok(Box::pin(stream))
+        // Only the exact sentinel ends the stream, chunks may contain the text "[DONE]"
+        parse_event_stream_until(response, "OpenRouter", "[DONE]").await
     }
 
     /// Returns a list of available models.
@@ -498,23 +483,12 @@ impl Openrouter {
     /// - The response cannot be parsed.
     ///
     /// [`Error`]: latchlm_core::Error
-    #[allow(clippy::expect_used)]
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn models(&self) -> Result<Vec<ModelId<'_>>> {
-        let url = self.base_url.join("models").expect("Failed to join URL");
+        let url = join_url(&self.base_url, "models")?;
         let response = self.client.get(url).send().await?;
 
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let message = response.text().await?;
-
-            #[cfg(feature = "tracing")]
-            tracing::error!("API request failed: {}", &message);
-
-            return Err(Error::ApiError { status, message });
-        }
-
-        let response: ModelsList = response.json().await?;
+        let response: ModelsList = parse_json(response).await?;
 
         Ok(response.into())
     }
@@ -0,0 +1,131 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! This module contains the structs used to serialize
+//! the OpenRouter API requests
+
+use latchlm_core::{AiRequest, ReasoningEffort, ReasoningOptions};
+use serde::Serialize;
+
+use crate::OpenrouterModel;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct ReasoningConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effort: Option<ReasoningEffort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u64>,
+    exclude: bool,
+}
+
+impl From<ReasoningOptions> for ReasoningConfig {
+    fn from(value: ReasoningOptions) -> Self {
+        // OpenRouter accepts either an effort or a token budget, not both.
+        let effort = value.effort.filter(|_| value.budget_tokens.is_none());
+
+        Self {
+            effort,
+            max_tokens: value.budget_tokens,
+            exclude: !value.include_reasoning,
+        }
+    }
+}
+
+/// The body of a request to the chat completions endpoint.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OpenrouterRequest {
+    model: String,
+    messages: Vec<RequestMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<ReasoningConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+}
+
+impl OpenrouterRequest {
+    pub(crate) fn new(model: &OpenrouterModel, request: AiRequest, stream: bool) -> Self {
+        Self {
+            model: model.as_ref().to_owned(),
+            messages: vec![RequestMessage {
+                role: "user",
+                content: request.text,
+            }],
+            stream,
+            reasoning: request.reasoning.map(Into::into),
+            n: request.candidate_count,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_text_only() {
+        let request = OpenrouterRequest::new(
+            &OpenrouterModel::new("openai/gpt-oss-20b"),
+            AiRequest {
+                text: "Hello".into(),
+                ..Default::default()
+            },
+            true,
+        );
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            serde_json::json!({
+                "model": "openai/gpt-oss-20b",
+                "messages": [{"role": "user", "content": "Hello"}],
+                "stream": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_reasoning() {
+        let request = OpenrouterRequest::new(
+            &OpenrouterModel::new("openai/gpt-oss-20b"),
+            AiRequest {
+                text: "Hello".into(),
+                reasoning: Some(ReasoningOptions {
+                    effort: Some(ReasoningEffort::High),
+                    budget_tokens: Some(2048),
+                    include_reasoning: false,
+                }),
+                ..Default::default()
+            },
+            false,
+        );
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap()["reasoning"],
+            serde_json::json!({"max_tokens": 2048, "exclude": true})
+        );
+    }
+
+    #[test]
+    fn test_serialize_candidate_count() {
+        let request = OpenrouterRequest::new(
+            &OpenrouterModel::new("openai/gpt-oss-20b"),
+            AiRequest {
+                text: "Hello".into(),
+                candidate_count: Some(3),
+                ..Default::default()
+            },
+            false,
+        );
+
+        assert_eq!(serde_json::to_value(request).unwrap()["n"], 3);
+    }
+}
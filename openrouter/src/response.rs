@@ -5,14 +5,47 @@
 //! This module contains the structs used to deserialize
 //! the OpenRouter API responses
 
-use latchlm_core::{AiResponse, ModelId, TokenUsage};
+use latchlm_core::{
+    AiResponse, Candidate, FinishReason, Modality, ModelCapabilities, ModelId, TokenUsage,
+};
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PromptTokensDetails {
+    cached_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompletionTokensDetails {
+    reasoning_tokens: Option<u64>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Usage {
     prompt_tokens: u64,
     completion_tokens: u64,
     total_tokens: u64,
+    prompt_tokens_details: Option<PromptTokensDetails>,
+    completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+impl From<&Usage> for TokenUsage {
+    fn from(value: &Usage) -> Self {
+        Self {
+            input_tokens: Some(value.prompt_tokens),
+            cached_input_tokens: value
+                .prompt_tokens_details
+                .as_ref()
+                .and_then(|details| details.cached_tokens),
+            output_tokens: Some(value.completion_tokens),
+            reasoning_tokens: value
+                .completion_tokens_details
+                .as_ref()
+                .and_then(|details| details.reasoning_tokens),
+            total_tokens: Some(value.total_tokens),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -20,14 +53,31 @@ pub struct Message {
     role: String,
     content: String,
     refusal: Option<serde_json::Value>,
-    reasoning: Option<serde_json::Value>,
+    reasoning: Option<String>,
+}
+
+/// Maps an OpenRouter finish reason to a [`FinishReason`].
+///
+/// OpenRouter normalizes the finish reasons of all providers, the provider's own
+/// reason is only used when it could not.
+fn normalize_finish_reason(
+    finish_reason: &str,
+    native_finish_reason: Option<&str>,
+) -> FinishReason {
+    match finish_reason {
+        "stop" => FinishReason::Stop,
+        "length" => FinishReason::Length,
+        "content_filter" => FinishReason::ContentFilter,
+        "tool_calls" => FinishReason::ToolCall,
+        other => FinishReason::Other(native_finish_reason.unwrap_or(other).to_owned()),
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Choice {
     logprobs: Option<serde_json::Value>,
-    finish_reason: String,
-    native_finish_reason: String,
+    finish_reason: Option<String>,
+    native_finish_reason: Option<String>,
     index: u64,
     message: Message,
 }
@@ -45,35 +95,126 @@ pub struct OpenrouterResponse {
 }
 
 impl OpenrouterResponse {
+    /// Returns the text of the first choice.
     #[must_use]
     pub fn extract_text(&self) -> String {
         self.choices
-            .iter()
+            .first()
             .map(|choice| choice.message.content.clone())
-            .collect::<Vec<_>>()
-            .join(" ")
+            .unwrap_or_default()
+    }
+
+    /// Returns every choice of the response.
+    #[must_use]
+    pub fn extract_candidates(&self) -> Vec<Candidate> {
+        self.choices
+            .iter()
+            .map(|choice| Candidate {
+                index: choice.index,
+                text: choice.message.content.clone(),
+                reasoning: choice.message.reasoning.clone(),
+                finish_reason: choice.finish_reason.as_deref().map(|reason| {
+                    normalize_finish_reason(reason, choice.native_finish_reason.as_deref())
+                }),
+            })
+            .collect()
+    }
+
+    /// Returns the reasoning of the first choice, if any.
+    #[must_use]
+    pub fn extract_reasoning(&self) -> Option<String> {
+        self.choices.first()?.message.reasoning.clone()
+    }
+
+    /// Returns the normalized reason the first choice stopped generating.
+    #[must_use]
+    pub fn finish_reason(&self) -> Option<FinishReason> {
+        let choice = self.choices.first()?;
+
+        choice
+            .finish_reason
+            .as_deref()
+            .map(|reason| normalize_finish_reason(reason, choice.native_finish_reason.as_deref()))
     }
 }
 
 impl From<OpenrouterResponse> for AiResponse {
     fn from(response: OpenrouterResponse) -> Self {
-        let text = response.extract_text();
-
         Self {
-            text,
-            token_usage: TokenUsage {
-                input_tokens: Some(response.usage.prompt_tokens),
-                output_tokens: Some(response.usage.completion_tokens),
-                total_tokens: Some(response.usage.total_tokens),
-            },
+            text: response.extract_text(),
+            reasoning: response.extract_reasoning(),
+            token_usage: (&response.usage).into(),
+            finish_reason: response.finish_reason(),
+            safety: None,
+            candidates: response.extract_candidates(),
+            citations: Vec::new(),
         }
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Architecture {
+    #[serde(default)]
+    input_modalities: Vec<String>,
+    #[serde(default)]
+    output_modalities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TopProvider {
+    context_length: Option<u64>,
+    max_completion_tokens: Option<u64>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ModelsItem {
     id: String,
     name: String,
+    context_length: Option<u64>,
+    #[serde(default)]
+    architecture: Architecture,
+    #[serde(default)]
+    top_provider: TopProvider,
+    #[serde(default)]
+    supported_parameters: Vec<String>,
+}
+
+fn modalities(names: &[String]) -> Vec<Modality> {
+    names
+        .iter()
+        .map(|name| match name.as_str() {
+            "text" => Modality::Text,
+            "image" => Modality::Image,
+            "audio" => Modality::Audio,
+            "video" => Modality::Video,
+            "file" => Modality::Document,
+            _ => Modality::Unspecified,
+        })
+        .collect()
+}
+
+impl ModelsItem {
+    fn capabilities(&self) -> ModelCapabilities<'static> {
+        let supports = |parameter: &str| {
+            Some(
+                self.supported_parameters
+                    .iter()
+                    .any(|supported| supported == parameter),
+            )
+        };
+
+        ModelCapabilities {
+            context_window: self.context_length.or(self.top_provider.context_length),
+            max_output_tokens: self.top_provider.max_completion_tokens,
+            input_modalities: modalities(&self.architecture.input_modalities).into(),
+            output_modalities: modalities(&self.architecture.output_modalities).into(),
+            tool_calling: supports("tools"),
+            structured_output: supports("structured_outputs"),
+            reasoning: supports("reasoning"),
+            // OpenRouter can stream the responses of every model
+            streaming: Some(true),
+        }
+    }
 }
 
 /// Represents a list of available models.
@@ -87,8 +228,10 @@ impl From<ModelsList> for Vec<ModelId<'_>> {
         let mut list = vec![];
         for model in value.data {
             list.push(ModelId {
+                capabilities: model.capabilities(),
                 id: model.id.into(),
                 name: model.name.into(),
+                deprecated: None,
             });
         }
         list
@@ -99,6 +242,7 @@ impl From<ModelsList> for Vec<ModelId<'_>> {
 pub struct StreamDelta {
     pub role: Option<String>,
     pub content: Option<String>,
+    pub reasoning: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -126,23 +270,57 @@ impl From<OpenrouterStreamResponse> for AiResponse {
     fn from(response: OpenrouterStreamResponse) -> Self {
         Self {
             text: response.extract_text(),
-            token_usage: TokenUsage {
-                input_tokens: response.usage.as_ref().map(|usage| usage.prompt_tokens),
-                output_tokens: response.usage.as_ref().map(|usage| usage.completion_tokens),
-                total_tokens: response.usage.as_ref().map(|usage| usage.total_tokens),
-            },
+            reasoning: response.extract_reasoning(),
+            token_usage: response.usage.as_ref().map(Into::into).unwrap_or_default(),
+            finish_reason: response.finish_reason(),
+            safety: None,
+            candidates: response.extract_candidates(),
+            citations: Vec::new(),
         }
     }
 }
 
 impl OpenrouterStreamResponse {
+    /// Returns the text delta of the first choice.
     #[must_use]
     pub fn extract_text(&self) -> String {
+        self.choices
+            .first()
+            .and_then(|choice| choice.delta.content.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the delta of every choice of the chunk.
+    #[must_use]
+    pub fn extract_candidates(&self) -> Vec<Candidate> {
         self.choices
             .iter()
-            .map(|choice| choice.delta.content.clone().unwrap_or_default())
-            .collect::<Vec<String>>()
-            .join(" ")
+            .map(|choice| Candidate {
+                index: choice.index,
+                text: choice.delta.content.clone().unwrap_or_default(),
+                reasoning: choice.delta.reasoning.clone(),
+                finish_reason: choice.finish_reason.as_deref().map(|reason| {
+                    normalize_finish_reason(reason, choice.native_finish_reason.as_deref())
+                }),
+            })
+            .collect()
+    }
+
+    /// Returns the reasoning delta of the first choice, if any.
+    #[must_use]
+    pub fn extract_reasoning(&self) -> Option<String> {
+        self.choices.first()?.delta.reasoning.clone()
+    }
+
+    /// Returns the normalized finish reason of the chunk, only set on the last chunk.
+    #[must_use]
+    pub fn finish_reason(&self) -> Option<FinishReason> {
+        let choice = self.choices.first()?;
+
+        choice
+            .finish_reason
+            .as_deref()
+            .map(|reason| normalize_finish_reason(reason, choice.native_finish_reason.as_deref()))
     }
 }
 
@@ -178,6 +356,7 @@ mod tests {
                     ..Default::default()
                 },
                 Choice {
+                    index: 1,
                     message: Message {
                         content: "Second Choice".to_string(),
                         ..Default::default()
@@ -188,7 +367,12 @@ mod tests {
             ..Default::default()
         };
 
-        assert_eq!(test_response.extract_text(), "First Choice Second Choice");
+        assert_eq!(test_response.extract_text(), "First Choice");
+
+        let candidates = test_response.extract_candidates();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[1].index, 1);
+        assert_eq!(candidates[1].text, "Second Choice");
     }
 
     #[test]
@@ -207,4 +391,101 @@ mod tests {
 
         assert_eq!(test_response.extract_text(), "");
     }
+
+    #[test]
+    fn test_extract_reasoning() {
+        let test_response = OpenrouterResponse {
+            choices: vec![Choice {
+                message: Message {
+                    content: "Answer".to_string(),
+                    reasoning: Some("Let me think.".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            usage: Usage {
+                prompt_tokens_details: Some(PromptTokensDetails {
+                    cached_tokens: Some(3),
+                }),
+                completion_tokens_details: Some(CompletionTokensDetails {
+                    reasoning_tokens: Some(12),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let response = AiResponse::from(test_response);
+        assert_eq!(response.text, "Answer");
+        assert_eq!(response.reasoning.as_deref(), Some("Let me think."));
+        assert_eq!(response.token_usage.reasoning_tokens, Some(12));
+        assert_eq!(response.token_usage.cached_input_tokens, Some(3));
+    }
+
+    #[test]
+    fn test_reasoning_of_each_choice() {
+        let choice = |index, reasoning: &str| Choice {
+            index,
+            message: Message {
+                reasoning: Some(reasoning.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let test_response = OpenrouterResponse {
+            choices: vec![choice(0, "First"), choice(1, "Second")],
+            ..Default::default()
+        };
+
+        let response = AiResponse::from(test_response);
+        assert_eq!(response.reasoning.as_deref(), Some("First"));
+        assert_eq!(response.candidates[1].reasoning.as_deref(), Some("Second"));
+    }
+
+    #[test]
+    fn test_stream_extract_reasoning() {
+        let chunk = OpenrouterStreamResponse {
+            choices: vec![StreamChoice {
+                delta: StreamDelta {
+                    reasoning: Some("Hmm".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(chunk.extract_reasoning().as_deref(), Some("Hmm"));
+        assert_eq!(
+            OpenrouterStreamResponse::default().extract_reasoning(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_finish_reason() {
+        let test_response = OpenrouterResponse {
+            choices: vec![Choice {
+                finish_reason: Some("length".to_string()),
+                native_finish_reason: Some("MAX_TOKENS".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(test_response.finish_reason(), Some(FinishReason::Length));
+
+        let chunk = OpenrouterStreamResponse {
+            choices: vec![StreamChoice {
+                finish_reason: Some("error".to_string()),
+                native_finish_reason: Some("MALFORMED_FUNCTION_CALL".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            AiResponse::from(chunk).finish_reason,
+            Some(FinishReason::Other("MALFORMED_FUNCTION_CALL".to_string()))
+        );
+        assert_eq!(OpenrouterStreamResponse::default().finish_reason(), None);
+    }
 }
@@ -5,12 +5,14 @@
 #![allow(clippy::unwrap_used)]
 #![allow(clippy::expect_used)]
 
-use latchlm_core::{AiProvider, AiRequest, Error};
+use futures::StreamExt;
+use latchlm_core::{AiProvider, AiRequest, Error, Modality, StaticCredential};
 use latchlm_openrouter::{Openrouter, OpenrouterModel};
+use latchlm_test_support::exact_text;
 use secrecy::{ExposeSecret, SecretString};
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{bearer_token, body_partial_json, method},
+    matchers::{bearer_token, body_partial_json, method, path},
 };
 
 #[tokio::test]
@@ -69,6 +71,7 @@ async fn test_request_response() {
             &model,
             AiRequest {
                 text: "Test Message".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -107,6 +110,7 @@ async fn test_error_unauthenticated() {
             &model,
             AiRequest {
                 text: "Test Message".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -149,6 +153,7 @@ async fn test_error_invalid_model() {
             &invalid_model,
             AiRequest {
                 text: "Test Message".to_owned(),
+                ..Default::default()
             },
         )
         .await
@@ -226,4 +231,202 @@ async fn test_models_endpoint() {
     assert_eq!(models.len(), 1);
     assert_eq!(models[0].id, "openai/gpt-5");
     assert_eq!(models[0].name, "OpenAI: GPT-5");
+
+    let capabilities = &models[0].capabilities;
+    assert_eq!(capabilities.context_window, Some(128000));
+    assert_eq!(capabilities.max_output_tokens, Some(16384));
+    assert!(capabilities.accepts(Modality::Image));
+    assert!(capabilities.generates(Modality::Text));
+    assert_eq!(capabilities.tool_calling, Some(false));
+}
+
+#[tokio::test]
+async fn test_streaming_preserves_exact_text() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = mock_server.uri();
+
+    let mut body = exact_text::PARTS
+        .iter()
+        .map(|content| {
+            let chunk = serde_json::json!({
+                "id": "gen-123",
+                "provider": "OpenAI",
+                "model": "openai/gpt-oss-20b",
+                "object": "chat.completion.chunk",
+                "created": 1754828429,
+                "choices": [{"index": 0, "delta": {"role": "assistant", "content": content}}]
+            });
+
+            format!(": OPENROUTER PROCESSING\n\ndata: {chunk}\n\n")
+        })
+        .collect::<String>();
+    body.push_str("data: [DONE]\n\n");
+
+    let _mock_guard = Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let test_client = Openrouter::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url.parse().expect("Failed to parse URL"),
+        SecretString::from("api-key"),
+    );
+
+    let text = test_client
+        .send_streaming(
+            &OpenrouterModel::new("openai/gpt-oss-20b"),
+            AiRequest::default(),
+        )
+        .map(|chunk| chunk.expect("Failed to receive chunk").text)
+        .collect::<String>()
+        .await;
+
+    exact_text::assert_preserved(&text);
+}
+
+#[tokio::test]
+async fn test_request_preserves_exact_text() {
+    let mock_server = MockServer::start().await;
+    let mock_base_url = mock_server.uri();
+
+    let content = exact_text::PARTS.concat();
+    let mock_response_body = serde_json::json!({
+        "id": "gen-123",
+        "provider": "OpenAI",
+        "model": "openai/gpt-oss-20b",
+        "object": "chat.completion",
+        "created": 1754828429,
+        "choices": [{
+            "finish_reason": "stop",
+            "native_finish_reason": "stop",
+            "index": 0,
+            "message": {"role": "assistant", "content": content}
+        }],
+        "usage": {"prompt_tokens": 8, "completion_tokens": 40, "total_tokens": 48}
+    });
+
+    let _mock_guard = Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_response_body))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let test_client = Openrouter::new_with_base_url(
+        reqwest::Client::new(),
+        mock_base_url.parse().expect("Failed to parse URL"),
+        SecretString::from("api-key"),
+    );
+
+    let response = test_client
+        .send_request(
+            &OpenrouterModel::new("openai/gpt-oss-20b"),
+            AiRequest::default(),
+        )
+        .await
+        .expect("Failed to send request");
+
+    exact_text::assert_preserved(&response.text);
+}
+
+#[tokio::test]
+async fn test_base_url_keeps_path_prefix() {
+    let mock_server = MockServer::start().await;
+    let base_url = reqwest::Url::parse(&format!("{}/proxy/openrouter/api/v1", mock_server.uri()))
+        .expect("Failed to parse URL");
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/proxy/openrouter/api/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "gen-123",
+            "provider": "OpenAI",
+            "model": "openai/gpt-oss-20b:free",
+            "object": "chat.completion",
+            "created": 1754828429,
+            "choices": [
+                {
+                    "logprobs": null,
+                    "finish_reason": "stop",
+                    "native_finish_reason": "stop",
+                    "index": 0,
+                    "message": {
+                        "role": "assistant",
+                        "content": "Hi",
+                        "refusal": null,
+                        "reasoning": null
+                    }
+                }
+            ],
+            "usage": {
+                "prompt_tokens": 1,
+                "completion_tokens": 1,
+                "total_tokens": 2
+            }
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openrouter::builder()
+        .client(reqwest::Client::new())
+        .api_key(SecretString::from("test-api-key"))
+        .base_url(base_url)
+        .build()
+        .expect("Failed to build client");
+
+    let response = test_client
+        .send_request(
+            &OpenrouterModel::new("openai/gpt-oss-20b:free"),
+            AiRequest::default(),
+        )
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hi");
+}
+
+#[tokio::test]
+async fn test_sends_provided_credentials() {
+    let mock_server = MockServer::start().await;
+
+    let _mock_guard = Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(bearer_token("provided_api_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "gen-123",
+            "provider": "OpenAI",
+            "model": "openai/gpt-oss-20b",
+            "object": "chat.completion",
+            "created": 1754828429,
+            "choices": [{
+                "finish_reason": "stop",
+                "native_finish_reason": "stop",
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"}
+            }],
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        })))
+        .expect(1)
+        .mount_as_scoped(&mock_server)
+        .await;
+
+    let test_client = Openrouter::builder()
+        .client(reqwest::Client::new())
+        .credentials(StaticCredential::new(SecretString::from(
+            "provided_api_key",
+        )))
+        .base_url(mock_server.uri().parse().expect("Failed to parse URL"))
+        .build()
+        .expect("Failed to build client");
+
+    let response = test_client
+        .send_request(
+            &OpenrouterModel::new("openai/gpt-oss-20b"),
+            AiRequest::default(),
+        )
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.text, "Hi");
 }
@@ -0,0 +1,34 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! A response split into chunks at awkward boundaries, shared by the provider tests
+//! checking that generated text reaches the caller unchanged.
+
+/// A fenced code block followed by a JSON document. The JSON contains the `[DONE]` stream
+/// sentinel as a string value.
+pub const PARTS: [&str; 7] = [
+    "```rust\nfn main() {\n",
+    "    println!(\"Hello, ",
+    "world!\");\n}\n```\n",
+    "{\"name\": \"latch",
+    "lm\",\n  \"tags\": [\"a\",",
+    " \"[DONE]\"]",
+    "}",
+];
+
+/// Asserts that `text` is the concatenation of [`PARTS`], and that its JSON document is
+/// still valid.
+pub fn assert_preserved(text: &str) {
+    assert_eq!(text, PARTS.concat());
+
+    let Some((_, json)) = text.split_once("```\n") else {
+        panic!("Missing code block in {text:?}");
+    };
+    let value: serde_json::Value =
+        serde_json::from_str(json).unwrap_or_else(|err| panic!("Invalid JSON: {err}"));
+    assert_eq!(
+        value,
+        serde_json::json!({"name": "latchlm", "tags": ["a", "[DONE]"]})
+    );
+}